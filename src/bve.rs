@@ -1,8 +1,16 @@
 mod ats;
 mod constant;
+mod handle;
+mod library;
+mod plugin;
+mod sound;
 mod structure;
 mod unit;
 
 pub use ats::*;
-pub use constant::*;
+pub use handle::*;
+pub use library::*;
+pub use plugin::*;
+pub use sound::*;
 pub use structure::*;
+pub use unit::*;
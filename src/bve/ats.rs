@@ -1,37 +1,53 @@
-use crate::bve::structure::{PanelId, SoundControl, SoundId};
+use crate::bve::structure::{OutOfRange, PanelId, SoundControl, SoundId};
 use crate::bve::{
-    Beacon, Handles, Horn, Key, NotchPosition, ReverserPosition, VehicleSpec, VehicleState,
+    Beacon, HandleInitialPosition, Handles, Horn, Key, NotchPosition, ReverserPosition,
+    VehicleSpec, VehicleState,
 };
 use std::ffi::c_int;
 
 pub struct PanelSound<'a> {
     panel: &'a mut [c_int; 256],
-    sound: &'a mut [SoundControl; 256],
+    sound: &'a mut [c_int; 256],
 }
 
 impl<'a> PanelSound<'a> {
-    pub fn new(panel: &'a mut [c_int; 256], sound: &'a mut [SoundControl; 256]) -> Self {
+    pub fn new(panel: &'a mut [c_int; 256], sound: &'a mut [c_int; 256]) -> Self {
         Self { panel, sound }
     }
     pub fn set_panel(&mut self, panel: PanelId, value: c_int) {
         self.panel[panel.0 as usize] = value;
     }
     pub fn set_sound(&mut self, sound: SoundId, value: SoundControl) {
-        self.sound[sound.0 as usize] = value;
+        self.sound[sound.0 as usize] = value.into();
+    }
+    // Value left in the slot by the previous frame
+    pub fn sound(&self, sound: SoundId) -> Result<SoundControl, OutOfRange> {
+        SoundControl::try_from(self.sound[sound.0 as usize])
     }
 }
 
+// One plugin instance; created on Load and dropped on Dispose
 pub trait AtsModule {
-    fn set_vehicle_spec(spec: &VehicleSpec);
-    fn tick(state: &VehicleState) -> Handles;
-    fn power(power: NotchPosition);
-    fn brake(brake: NotchPosition);
-    fn reverser(reverser: ReverserPosition);
-    fn key_down(_key: Key) {}
-    fn key_up(_key: Key) {}
-    fn horn_brow(_horn: Horn) {}
-    fn open_door() {}
-    fn close_door() {}
-    fn set_signal(_signal: c_int) {}
-    fn receive_beacon(beacon: &Beacon);
+    fn load() -> Self
+    where
+        Self: Sized;
+    fn dispose(&mut self) {}
+    // Lamp lit by the plugin holder once the module has faulted
+    fn fault_lamp(&self) -> Option<PanelId> {
+        None
+    }
+    fn set_vehicle_spec(&mut self, spec: &VehicleSpec);
+    // Called on every (re)start of a run, including station jumps: drop per-run state here
+    fn initialize(&mut self, _handle: HandleInitialPosition) {}
+    fn tick(&mut self, state: &VehicleState, output: &mut PanelSound) -> Handles;
+    fn power(&mut self, power: NotchPosition);
+    fn brake(&mut self, brake: NotchPosition);
+    fn reverser(&mut self, reverser: ReverserPosition);
+    fn key_down(&mut self, _key: Key) {}
+    fn key_up(&mut self, _key: Key) {}
+    fn horn_brow(&mut self, _horn: Horn) {}
+    fn open_door(&mut self) {}
+    fn close_door(&mut self) {}
+    fn set_signal(&mut self, _signal: c_int) {}
+    fn receive_beacon(&mut self, beacon: &Beacon);
 }
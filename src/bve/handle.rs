@@ -0,0 +1,154 @@
+use crate::bve::{NotchCount, NotchPosition};
+
+// Brake strength independent of the vehicle's notch layout
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum BrakeCommand {
+    Release,
+    // Ratio of the full service brake, rounded up to the next notch
+    Service(f64),
+    // 80% brake (67 degree)
+    B67,
+    FullService,
+    Emergency,
+}
+
+// Notch layout of the vehicle, derived from VehicleSpec
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct HandleModel {
+    brake_notches: NotchCount,
+    power_notches: NotchCount,
+    ats_notch: NotchPosition,
+    b67_notch: NotchPosition,
+}
+
+impl HandleModel {
+    pub fn new(
+        brake_notches: NotchCount,
+        power_notches: NotchCount,
+        ats_notch: NotchPosition,
+        b67_notch: NotchPosition,
+    ) -> Self {
+        Self {
+            brake_notches,
+            power_notches,
+            ats_notch,
+            b67_notch,
+        }
+    }
+    pub fn full_service_brake(&self) -> NotchPosition {
+        self.brake_notches.full()
+    }
+    pub fn emergency_brake(&self) -> NotchPosition {
+        NotchPosition(self.brake_notches.full().0 + 1)
+    }
+    pub fn b67_brake(&self) -> NotchPosition {
+        self.b67_notch
+    }
+    // Notch the driver has to reach to confirm (cancel) an ATS brake
+    pub fn ats_confirm_brake(&self) -> NotchPosition {
+        self.ats_notch
+    }
+    pub fn max_power(&self) -> NotchPosition {
+        self.power_notches.full()
+    }
+    pub fn is_service(&self, brake: NotchPosition) -> bool {
+        NotchPosition::NEUTRAL < brake && brake <= self.full_service_brake()
+    }
+    pub fn is_at_or_above_b67(&self, brake: NotchPosition) -> bool {
+        brake >= self.b67_notch
+    }
+    pub fn is_ats_confirmed(&self, brake: NotchPosition) -> bool {
+        brake >= self.ats_notch
+    }
+    pub fn is_emergency(&self, brake: NotchPosition) -> bool {
+        brake >= self.emergency_brake()
+    }
+    pub fn brake(&self, command: BrakeCommand) -> NotchPosition {
+        match command {
+            BrakeCommand::Release => NotchPosition::NEUTRAL,
+            BrakeCommand::Service(ratio) if ratio <= 0. => NotchPosition::NEUTRAL,
+            // A vehicle without service notches can only brake with the emergency brake
+            BrakeCommand::Service(_) | BrakeCommand::B67 | BrakeCommand::FullService
+                if self.full_service_brake().0 < 1 =>
+            {
+                self.emergency_brake()
+            }
+            BrakeCommand::Service(ratio) => {
+                let full = self.full_service_brake().0;
+                let notch = (ratio * full as f64).ceil() as i32;
+                NotchPosition(notch.clamp(1, full))
+            }
+            BrakeCommand::B67 => self.b67_notch,
+            BrakeCommand::FullService => self.full_service_brake(),
+            BrakeCommand::Emergency => self.emergency_brake(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model() -> HandleModel {
+        HandleModel::new(
+            NotchCount(8),
+            NotchCount(5),
+            NotchPosition(1),
+            NotchPosition(6),
+        )
+    }
+
+    #[test]
+    fn notches() {
+        let model = model();
+        assert_eq!(NotchPosition(8), model.full_service_brake());
+        assert_eq!(NotchPosition(9), model.emergency_brake());
+        assert_eq!(NotchPosition(6), model.b67_brake());
+        assert_eq!(NotchPosition(1), model.ats_confirm_brake());
+        assert_eq!(NotchPosition(5), model.max_power());
+    }
+    #[test]
+    fn classify() {
+        let model = model();
+        assert!(!model.is_service(NotchPosition(0)));
+        assert!(model.is_service(NotchPosition(8)));
+        assert!(!model.is_service(NotchPosition(9)));
+        assert!(!model.is_at_or_above_b67(NotchPosition(5)));
+        assert!(model.is_at_or_above_b67(NotchPosition(6)));
+        assert!(model.is_ats_confirmed(NotchPosition(1)));
+        assert!(!model.is_emergency(NotchPosition(8)));
+        assert!(model.is_emergency(NotchPosition(9)));
+    }
+    #[test]
+    fn brake() {
+        let model = model();
+        assert_eq!(NotchPosition(0), model.brake(BrakeCommand::Release));
+        assert_eq!(NotchPosition(0), model.brake(BrakeCommand::Service(0.)));
+        assert_eq!(NotchPosition(1), model.brake(BrakeCommand::Service(0.01)));
+        assert_eq!(NotchPosition(4), model.brake(BrakeCommand::Service(0.5)));
+        assert_eq!(NotchPosition(8), model.brake(BrakeCommand::Service(2.)));
+        assert_eq!(NotchPosition(6), model.brake(BrakeCommand::B67));
+        assert_eq!(NotchPosition(8), model.brake(BrakeCommand::FullService));
+        assert_eq!(NotchPosition(9), model.brake(BrakeCommand::Emergency));
+    }
+    #[test]
+    fn no_service_notches() {
+        let model = HandleModel::new(
+            NotchCount(0),
+            NotchCount(5),
+            NotchPosition(0),
+            NotchPosition(0),
+        );
+        assert_eq!(NotchPosition(0), model.brake(BrakeCommand::Service(0.)));
+        assert_eq!(NotchPosition(1), model.brake(BrakeCommand::Service(0.5)));
+        assert_eq!(
+            model.emergency_brake(),
+            model.brake(BrakeCommand::Service(1.))
+        );
+        assert_eq!(
+            model.emergency_brake(),
+            model.brake(BrakeCommand::FullService)
+        );
+        assert_eq!(model.emergency_brake(), model.brake(BrakeCommand::B67));
+    }
+}
@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+// Path of the shared library this plugin was loaded from, so that files shipped next to it
+// can be found whatever the host's working directory is
+pub fn library_path() -> Option<PathBuf> {
+    imp::library_path()
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::ffi::{c_char, c_int, c_void, CStr, OsStr};
+    use std::mem::MaybeUninit;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::PathBuf;
+
+    #[repr(C)]
+    struct DlInfo {
+        dli_fname: *const c_char,
+        dli_fbase: *mut c_void,
+        dli_sname: *const c_char,
+        dli_saddr: *mut c_void,
+    }
+
+    extern "C" {
+        fn dladdr(addr: *const c_void, info: *mut DlInfo) -> c_int;
+    }
+
+    pub fn library_path() -> Option<PathBuf> {
+        let mut info = MaybeUninit::<DlInfo>::zeroed();
+        // Any address inside this library identifies it
+        let found = unsafe { dladdr(library_path as *const c_void, info.as_mut_ptr()) };
+        if found == 0 {
+            return None;
+        }
+        let info = unsafe { info.assume_init() };
+        if info.dli_fname.is_null() {
+            return None;
+        }
+        let name = unsafe { CStr::from_ptr(info.dli_fname) };
+        Some(PathBuf::from(OsStr::from_bytes(name.to_bytes())))
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::ffi::{c_void, OsString};
+    use std::os::windows::ffi::OsStringExt;
+    use std::path::PathBuf;
+    use std::ptr::null_mut;
+
+    const GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT: u32 = 0x2;
+    const GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS: u32 = 0x4;
+    // Longest path the wide-character API can return
+    const MAX_PATH_WIDE: usize = 32768;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetModuleHandleExW(flags: u32, module_name: *const u16, module: *mut *mut c_void)
+            -> i32;
+        fn GetModuleFileNameW(module: *mut c_void, filename: *mut u16, size: u32) -> u32;
+    }
+
+    pub fn library_path() -> Option<PathBuf> {
+        let mut module = null_mut();
+        // Any address inside this DLL identifies it; the host keeps it loaded
+        let found = unsafe {
+            GetModuleHandleExW(
+                GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS
+                    | GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT,
+                library_path as *const u16,
+                &mut module,
+            )
+        };
+        if found == 0 {
+            return None;
+        }
+        let mut buffer = vec![0u16; MAX_PATH_WIDE];
+        let length =
+            unsafe { GetModuleFileNameW(module, buffer.as_mut_ptr(), buffer.len() as u32) };
+        if length == 0 || length as usize >= buffer.len() {
+            return None;
+        }
+        Some(PathBuf::from(OsString::from_wide(
+            &buffer[..length as usize],
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn found() {
+        // Within tests this is the test executable itself
+        let path = library_path().unwrap();
+        assert!(path.is_file());
+    }
+}
@@ -0,0 +1,229 @@
+use crate::bve::{
+    AtsModule, ConstantSpeed, Handles, NotchPosition, PanelId, PanelSound, ReverserPosition,
+    VehicleSpec, VehicleState,
+};
+use std::any::Any;
+use std::ffi::c_int;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+struct Instance<M> {
+    module: Option<M>,
+    spec: Option<VehicleSpec>,
+    reverser: ReverserPosition,
+    fault_lamp: Option<PanelId>,
+    // Latched once the module has panicked; cleared only by the next Load
+    fault: bool,
+}
+
+// Holds the single module instance between Load and Dispose.
+// Calls made outside of that window are ignored.
+//
+// Every call into the module is guarded against panics. A panic latches the plugin into
+// system fault: the module is no longer called and Elapse commands the emergency brake.
+pub struct PluginInstance<M: AtsModule> {
+    instance: Mutex<Instance<M>>,
+}
+
+impl<M: AtsModule> PluginInstance<M> {
+    pub const fn new() -> Self {
+        Self {
+            instance: Mutex::new(Instance {
+                module: None,
+                spec: None,
+                reverser: ReverserPosition::NEUTRAL,
+                fault_lamp: None,
+                fault: false,
+            }),
+        }
+    }
+    pub fn load(&self) {
+        self.dispose();
+        let mut instance = self.lock();
+        instance.fault = false;
+        match catch_unwind(M::load) {
+            Ok(module) => {
+                instance.fault_lamp = module.fault_lamp();
+                instance.module = Some(module);
+            }
+            Err(payload) => instance.latch_fault("Load", payload),
+        }
+    }
+    pub fn dispose(&self) {
+        let mut instance = self.lock();
+        if let Some(mut module) = instance.module.take() {
+            if let Err(payload) = catch_unwind(AssertUnwindSafe(|| module.dispose())) {
+                instance.latch_fault("Dispose", payload);
+            }
+        }
+    }
+    pub fn set_vehicle_spec(&self, spec: VehicleSpec) {
+        self.lock().spec = Some(spec);
+        self.with("SetVehicleSpec", |m| m.set_vehicle_spec(&spec));
+    }
+    pub fn reverser(&self, reverser: ReverserPosition) {
+        self.lock().reverser = reverser;
+        self.with("SetReverser", |m| m.reverser(reverser));
+    }
+    pub fn tick(
+        &self,
+        state: &VehicleState,
+        panel: &mut [c_int; 256],
+        sound: &mut [c_int; 256],
+    ) -> Handles {
+        let mut output = PanelSound::new(panel, sound);
+        if let Some(handles) = self.with("Elapse", |m| m.tick(state, &mut output)) {
+            return handles;
+        }
+        let instance = self.lock();
+        if !instance.fault {
+            return Handles {
+                power: NotchPosition::NEUTRAL,
+                brake: NotchPosition::NEUTRAL,
+                reverser: ReverserPosition::NEUTRAL,
+                constant_speed: ConstantSpeed::Disable,
+            };
+        }
+        if let Some(lamp) = instance.fault_lamp {
+            output.set_panel(lamp, 1);
+        }
+        Handles {
+            power: NotchPosition::NEUTRAL,
+            // Without a spec there is no notch known to be the emergency one
+            brake: instance.spec.map_or(NotchPosition::NEUTRAL, |spec| {
+                spec.handles().emergency_brake()
+            }),
+            reverser: instance.reverser,
+            constant_speed: ConstantSpeed::Disable,
+        }
+    }
+    // Runs `f` on the module unless it is unloaded or faulted
+    pub fn with<R>(&self, entry: &str, f: impl FnOnce(&mut M) -> R) -> Option<R> {
+        let mut instance = self.lock();
+        if instance.fault {
+            return None;
+        }
+        let module = instance.module.as_mut()?;
+        match catch_unwind(AssertUnwindSafe(|| f(module))) {
+            Ok(result) => Some(result),
+            Err(payload) => {
+                instance.latch_fault(entry, payload);
+                None
+            }
+        }
+    }
+    pub fn is_faulted(&self) -> bool {
+        self.lock().fault
+    }
+    fn lock(&self) -> MutexGuard<'_, Instance<M>> {
+        self.instance.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<M: AtsModule> Default for PluginInstance<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M> Instance<M> {
+    fn latch_fault(&mut self, entry: &str, payload: Box<dyn Any + Send>) {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown panic");
+        eprintln!("bve-koatc: panic in {entry}: {message}; latched system fault");
+        self.fault = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bve::{Beacon, HandleInitialPosition, Key};
+    use std::mem::zeroed;
+
+    struct Counter {
+        calls: u32,
+    }
+    impl AtsModule for Counter {
+        fn load() -> Self {
+            Counter { calls: 0 }
+        }
+        fn fault_lamp(&self) -> Option<PanelId> {
+            Some(PanelId(7))
+        }
+        fn set_vehicle_spec(&mut self, _spec: &VehicleSpec) {}
+        fn initialize(&mut self, _handle: HandleInitialPosition) {
+            self.calls = 0;
+        }
+        fn tick(&mut self, _state: &VehicleState, _output: &mut PanelSound) -> Handles {
+            Handles {
+                power: NotchPosition(1),
+                brake: NotchPosition::NEUTRAL,
+                reverser: ReverserPosition(1),
+                constant_speed: ConstantSpeed::Continue,
+            }
+        }
+        fn power(&mut self, _power: NotchPosition) {
+            self.calls += 1;
+        }
+        fn brake(&mut self, _brake: NotchPosition) {}
+        fn reverser(&mut self, _reverser: ReverserPosition) {}
+        fn key_down(&mut self, _key: Key) {
+            panic!("key");
+        }
+        fn receive_beacon(&mut self, _beacon: &Beacon) {}
+    }
+
+    // Plain-old-data structs from the host; all zero is a valid value
+    fn state() -> VehicleState {
+        unsafe { zeroed() }
+    }
+    fn spec() -> VehicleSpec {
+        unsafe { zeroed() }
+    }
+
+    #[test]
+    fn lifecycle() {
+        let plugin = PluginInstance::<Counter>::new();
+        assert_eq!(None, plugin.with("SetPower", |m| m.calls));
+        plugin.load();
+        plugin.with("SetPower", |m| m.power(NotchPosition(1)));
+        plugin.with("SetPower", |m| m.power(NotchPosition(2)));
+        assert_eq!(Some(2), plugin.with("SetPower", |m| m.calls));
+        plugin.with("Initialize", |m| {
+            m.initialize(HandleInitialPosition::ServiceBrake)
+        });
+        assert_eq!(Some(0), plugin.with("SetPower", |m| m.calls));
+        plugin.dispose();
+        assert_eq!(None, plugin.with("SetPower", |m| m.calls));
+    }
+    #[test]
+    fn fault() {
+        let plugin = PluginInstance::<Counter>::new();
+        let mut panel = [0; 256];
+        let mut sound = [0; 256];
+        plugin.load();
+        plugin.set_vehicle_spec(spec());
+        plugin.reverser(ReverserPosition(1));
+        assert_eq!(
+            NotchPosition(1),
+            plugin.tick(&state(), &mut panel, &mut sound).power
+        );
+        assert_eq!(0, panel[7]);
+
+        plugin.with("KeyDown", |m| m.key_down(Key::S));
+        assert!(plugin.is_faulted());
+        assert_eq!(None, plugin.with("SetPower", |m| m.calls));
+        let handles = plugin.tick(&state(), &mut panel, &mut sound);
+        assert_eq!(NotchPosition::NEUTRAL, handles.power);
+        assert_eq!(NotchPosition(1), handles.brake);
+        assert_eq!(ReverserPosition(1), handles.reverser);
+        assert_eq!(1, panel[7]);
+
+        plugin.load();
+        assert!(!plugin.is_faulted());
+    }
+}
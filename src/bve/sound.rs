@@ -0,0 +1,163 @@
+use crate::bve::unit::Time;
+use crate::bve::{PanelSound, SoundControl, SoundId};
+use std::collections::HashMap;
+use std::ffi::c_int;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Channel {
+    Idle,
+    Play,
+    StartLoop,
+    Looping,
+    StartChime(Time<c_int>),
+    Chiming { until: Time<c_int> },
+    Stop,
+}
+
+// Turns sound requests into the edge-triggered values BVE expects.
+// Each request is written once; every other frame the slot holds Continue, so calling
+// `start_loop` every frame does not restart the sound.
+pub struct SoundManager {
+    channels: HashMap<SoundId, Channel>,
+    now: Time<c_int>,
+}
+
+impl SoundManager {
+    pub fn new() -> Self {
+        Self {
+            channels: HashMap::new(),
+            now: Time::milliseconds(0),
+        }
+    }
+    // Plays the sound once from the beginning
+    pub fn play(&mut self, sound: SoundId) {
+        self.channels.insert(sound, Channel::Play);
+    }
+    pub fn start_loop(&mut self, sound: SoundId) {
+        let channel = self.channel(sound);
+        if !matches!(channel, Channel::StartLoop | Channel::Looping) {
+            *channel = Channel::StartLoop;
+        }
+    }
+    pub fn stop(&mut self, sound: SoundId) {
+        let channel = self.channel(sound);
+        if *channel != Channel::Idle {
+            *channel = Channel::Stop;
+        }
+    }
+    // Starts or stops a looping sound following `active`
+    pub fn set_loop(&mut self, sound: SoundId, active: bool) {
+        if active {
+            self.start_loop(sound);
+        } else {
+            self.stop(sound);
+        }
+    }
+    // Loops the sound for `duration`; a chime already ringing is extended instead of restarted
+    pub fn chime(&mut self, sound: SoundId, duration: Time<c_int>) {
+        let now = self.now;
+        let channel = self.channel(sound);
+        *channel = match *channel {
+            Channel::Chiming { until } if until < now + duration => Channel::Chiming {
+                until: now + duration,
+            },
+            chiming @ Channel::Chiming { .. } => chiming,
+            _ => Channel::StartChime(duration),
+        };
+    }
+    // Silences everything, e.g. when the run is restarted
+    pub fn stop_all(&mut self) {
+        for channel in self.channels.values_mut() {
+            if *channel != Channel::Idle {
+                *channel = Channel::Stop;
+            }
+        }
+    }
+    pub fn update(&mut self, now: Time<c_int>, output: &mut PanelSound) {
+        self.now = now;
+        for (&sound, channel) in &mut self.channels {
+            let (control, next) = match *channel {
+                Channel::Idle => (SoundControl::Continue, Channel::Idle),
+                Channel::Play => (SoundControl::Play, Channel::Idle),
+                Channel::StartLoop => (SoundControl::PlayLooping, Channel::Looping),
+                Channel::Looping => (SoundControl::Continue, Channel::Looping),
+                Channel::StartChime(duration) => (
+                    SoundControl::PlayLooping,
+                    Channel::Chiming {
+                        until: now + duration,
+                    },
+                ),
+                Channel::Chiming { until } if until <= now => (SoundControl::Stop, Channel::Idle),
+                chiming @ Channel::Chiming { .. } => (SoundControl::Continue, chiming),
+                Channel::Stop => (SoundControl::Stop, Channel::Idle),
+            };
+            output.set_sound(sound, control);
+            *channel = next;
+        }
+    }
+    fn channel(&mut self, sound: SoundId) -> &mut Channel {
+        self.channels.entry(sound).or_insert(Channel::Idle)
+    }
+}
+
+impl Default for SoundManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BELL: SoundId = SoundId(3);
+
+    fn frame(manager: &mut SoundManager, now: c_int) -> Result<SoundControl, c_int> {
+        let mut panel = [0; 256];
+        let mut sound = [0; 256];
+        let mut output = PanelSound::new(&mut panel, &mut sound);
+        manager.update(Time::milliseconds(now), &mut output);
+        output.sound(BELL).map_err(|e| e.0)
+    }
+
+    #[test]
+    fn play() {
+        let mut manager = SoundManager::new();
+        manager.play(BELL);
+        assert_eq!(Ok(SoundControl::Play), frame(&mut manager, 0));
+        assert_eq!(Ok(SoundControl::Continue), frame(&mut manager, 16));
+        manager.play(BELL);
+        assert_eq!(Ok(SoundControl::Play), frame(&mut manager, 32));
+    }
+    #[test]
+    fn looping() {
+        let mut manager = SoundManager::new();
+        manager.set_loop(BELL, true);
+        assert_eq!(Ok(SoundControl::PlayLooping), frame(&mut manager, 0));
+        manager.set_loop(BELL, true);
+        assert_eq!(Ok(SoundControl::Continue), frame(&mut manager, 16));
+        manager.set_loop(BELL, false);
+        assert_eq!(Ok(SoundControl::Stop), frame(&mut manager, 32));
+        manager.set_loop(BELL, false);
+        assert_eq!(Ok(SoundControl::Continue), frame(&mut manager, 48));
+    }
+    #[test]
+    fn chime() {
+        let mut manager = SoundManager::new();
+        manager.chime(BELL, Time::milliseconds(100));
+        assert_eq!(Ok(SoundControl::PlayLooping), frame(&mut manager, 1000));
+        assert_eq!(Ok(SoundControl::Continue), frame(&mut manager, 1050));
+        manager.chime(BELL, Time::milliseconds(100));
+        assert_eq!(Ok(SoundControl::Continue), frame(&mut manager, 1100));
+        assert_eq!(Ok(SoundControl::Stop), frame(&mut manager, 1150));
+        assert_eq!(Ok(SoundControl::Continue), frame(&mut manager, 1200));
+    }
+    #[test]
+    fn stop_all() {
+        let mut manager = SoundManager::new();
+        manager.start_loop(BELL);
+        assert_eq!(Ok(SoundControl::PlayLooping), frame(&mut manager, 0));
+        manager.stop_all();
+        assert_eq!(Ok(SoundControl::Stop), frame(&mut manager, 16));
+    }
+}
@@ -1,4 +1,5 @@
-use crate::bve::unit::{Length, Time};
+use crate::bve::handle::HandleModel;
+use crate::bve::unit::{ElectricCurrent, Length, Pressure, Time, Velocity};
 use std::ffi::c_double;
 use std::ffi::c_float;
 use std::ffi::c_int;
@@ -6,11 +7,15 @@ use std::ffi::c_int;
 #[repr(C)]
 #[derive(Debug)]
 pub struct BeaconType(pub u32);
-#[derive(Debug)]
+// Raw value passed by the host that does not name any known variant
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct OutOfRange(pub c_int);
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub struct PanelId(pub u8);
-#[derive(Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct SoundId(pub u8);
 #[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum Key {
     S = 0,
     A1 = 1,
@@ -29,29 +34,126 @@ pub enum Key {
     K = 14,
     L = 15,
 }
+impl TryFrom<c_int> for Key {
+    type Error = OutOfRange;
+
+    fn try_from(value: c_int) -> Result<Self, Self::Error> {
+        Ok(match value {
+            0 => Key::S,
+            1 => Key::A1,
+            2 => Key::A2,
+            3 => Key::B1,
+            4 => Key::B2,
+            5 => Key::C1,
+            6 => Key::C2,
+            7 => Key::D,
+            8 => Key::E,
+            9 => Key::F,
+            10 => Key::G,
+            11 => Key::H,
+            12 => Key::I,
+            13 => Key::J,
+            14 => Key::K,
+            15 => Key::L,
+            _ => return Err(OutOfRange(value)),
+        })
+    }
+}
 #[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum HandleInitialPosition {
     HandleRemoved = 2,
     EmergencyBrake = 1,
     ServiceBrake = 0,
 }
-#[repr(C)]
-#[derive(Copy, Clone, Debug)]
+impl TryFrom<c_int> for HandleInitialPosition {
+    type Error = OutOfRange;
+
+    fn try_from(value: c_int) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(HandleInitialPosition::ServiceBrake),
+            1 => Ok(HandleInitialPosition::EmergencyBrake),
+            2 => Ok(HandleInitialPosition::HandleRemoved),
+            _ => Err(OutOfRange(value)),
+        }
+    }
+}
+// Value of one slot of the sound array.
+// The host stores any integer there, so this is not a #[repr(C)] view of it.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum SoundControl {
-    Stop = -10000,
-    Play = 1,
-    PlayLooping = 0,
-    Continue = 2,
+    Stop,
+    Play,
+    PlayLooping,
+    Continue,
+    // Looping at a volume lowered by the given hundredths of decibel (1..=9999)
+    Attenuated(c_int),
+}
+impl SoundControl {
+    const STOP: c_int = -10000;
+    const PLAY_LOOPING: c_int = 0;
+    const PLAY: c_int = 1;
+    const CONTINUE: c_int = 2;
+
+    // Looping sound at a linear volume in 0.0..=1.0
+    pub fn looping_at(volume: f64) -> Self {
+        if volume >= 1. {
+            return SoundControl::PlayLooping;
+        }
+        let attenuation = (-2000. * volume.log10()).round();
+        if attenuation.is_nan() || attenuation >= -SoundControl::STOP as f64 {
+            SoundControl::Stop
+        } else {
+            SoundControl::Attenuated(attenuation as c_int)
+        }
+    }
+}
+impl From<SoundControl> for c_int {
+    fn from(value: SoundControl) -> Self {
+        match value {
+            SoundControl::Stop => SoundControl::STOP,
+            SoundControl::Play => SoundControl::PLAY,
+            SoundControl::PlayLooping => SoundControl::PLAY_LOOPING,
+            SoundControl::Continue => SoundControl::CONTINUE,
+            SoundControl::Attenuated(attenuation) => -attenuation.clamp(0, -SoundControl::STOP - 1),
+        }
+    }
+}
+impl TryFrom<c_int> for SoundControl {
+    type Error = OutOfRange;
+
+    fn try_from(value: c_int) -> Result<Self, Self::Error> {
+        match value {
+            ..=SoundControl::STOP => Ok(SoundControl::Stop),
+            SoundControl::PLAY_LOOPING => Ok(SoundControl::PlayLooping),
+            SoundControl::PLAY => Ok(SoundControl::Play),
+            SoundControl::CONTINUE => Ok(SoundControl::Continue),
+            _ if value < 0 => Ok(SoundControl::Attenuated(-value)),
+            _ => Err(OutOfRange(value)),
+        }
+    }
 }
 #[repr(C)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum Horn {
     Primary = 0,
     Secondary = 1,
     Music = 2,
 }
+impl TryFrom<c_int> for Horn {
+    type Error = OutOfRange;
+
+    fn try_from(value: c_int) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Horn::Primary),
+            1 => Ok(Horn::Secondary),
+            2 => Ok(Horn::Music),
+            _ => Err(OutOfRange(value)),
+        }
+    }
+}
 #[repr(C)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum ConstantSpeed {
     Continue = 0,
     Enable = 1,
@@ -76,7 +178,7 @@ impl NotchCount {
     }
 }
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug)]
 pub struct VehicleSpec {
     brake_notches: NotchCount, // Number of Brake Notches
     power_notches: NotchCount, // Number of Power Notches
@@ -84,6 +186,19 @@ pub struct VehicleSpec {
     b67_notch: NotchPosition,  // 80% Brake (67 degree)
     cars: c_int,               // Number of Cars
 }
+impl VehicleSpec {
+    pub fn handles(&self) -> HandleModel {
+        HandleModel::new(
+            self.brake_notches,
+            self.power_notches,
+            self.ats_notch,
+            self.b67_notch,
+        )
+    }
+    pub fn cars(&self) -> c_int {
+        self.cars
+    }
+}
 #[repr(C)]
 #[derive(Debug)]
 pub struct VehicleState {
@@ -95,12 +210,43 @@ pub struct VehicleState {
     er_pressure: Pressure,
     bp_pressure: Pressure,
     sap_pressure: Pressure,
-    current: c_float,
+    current: ElectricCurrent,
+}
+impl VehicleState {
+    pub fn location(&self) -> Length<c_double> {
+        Length::meters(self.location)
+    }
+    pub fn speed(&self) -> Velocity {
+        Velocity::kilometer_per_hour(self.speed)
+    }
+    pub fn time(&self) -> Time<c_int> {
+        self.time
+    }
+    // Brake Cylinder
+    pub fn bc_pressure(&self) -> Pressure {
+        self.bc_pressure
+    }
+    // Main Reservoir
+    pub fn mr_pressure(&self) -> Pressure {
+        self.mr_pressure
+    }
+    // Equalizing Reservoir
+    pub fn er_pressure(&self) -> Pressure {
+        self.er_pressure
+    }
+    // Brake Pipe
+    pub fn bp_pressure(&self) -> Pressure {
+        self.bp_pressure
+    }
+    // Straight Air Pipe
+    pub fn sap_pressure(&self) -> Pressure {
+        self.sap_pressure
+    }
+    // Motor current; negative while regenerating
+    pub fn current(&self) -> ElectricCurrent {
+        self.current
+    }
 }
-
-#[repr(C)]
-#[derive(Debug)]
-pub struct Pressure(pub c_int);
 
 // Received Data from Beacon
 #[repr(C)]
@@ -108,17 +254,66 @@ pub struct Pressure(pub c_int);
 pub struct Beacon {
     pub beacon_type: BeaconType,
     pub signal: c_int,
-    pub distance: Length<c_float>,
+    pub distance: c_float,
     pub optional: c_int,
 }
+impl Beacon {
+    // To the signal linked to the beacon
+    pub fn distance(&self) -> Length<c_float> {
+        Length::meters(self.distance)
+    }
+}
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub struct ReverserPosition(pub c_int);
+impl ReverserPosition {
+    pub const NEUTRAL: ReverserPosition = ReverserPosition(0);
+}
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug)]
 pub struct Handles {
     pub brake: NotchPosition,
     pub power: NotchPosition,
     pub reverser: ReverserPosition,
     pub constant_speed: ConstantSpeed,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sound_control() {
+        for value in [-10000, -2000, -1, 0, 1, 2] {
+            let control = SoundControl::try_from(value).unwrap();
+            assert_eq!(value, c_int::from(control));
+        }
+        assert_eq!(Ok(SoundControl::Stop), SoundControl::try_from(-20000));
+        assert_eq!(
+            Ok(SoundControl::Attenuated(600)),
+            SoundControl::try_from(-600)
+        );
+        assert_eq!(Err(OutOfRange(3)), SoundControl::try_from(3));
+    }
+    #[test]
+    fn looping_at() {
+        assert_eq!(SoundControl::PlayLooping, SoundControl::looping_at(1.));
+        assert_eq!(
+            SoundControl::Attenuated(2000),
+            SoundControl::looping_at(0.1)
+        );
+        assert_eq!(SoundControl::Attenuated(602), SoundControl::looping_at(0.5));
+        assert_eq!(SoundControl::Stop, SoundControl::looping_at(0.));
+        assert_eq!(SoundControl::Stop, SoundControl::looping_at(-1.));
+    }
+    #[test]
+    fn beacon_distance() {
+        let beacon = Beacon {
+            beacon_type: BeaconType(0),
+            signal: 0,
+            distance: 600.,
+            optional: 0,
+        };
+        assert_eq!(Length::meters(600.), beacon.distance());
+    }
+}
@@ -1,10 +1,18 @@
 mod acceleration;
+mod current;
+mod kinematics;
 mod length;
 #[cfg(test)]
 mod neareq;
+mod parse;
+mod pressure;
 mod time;
 mod velocity;
 
+pub use acceleration::*;
+pub use current::*;
 pub use length::*;
+pub use parse::ParseQuantityError;
+pub use pressure::*;
 pub use time::*;
 pub use velocity::*;
@@ -1,7 +1,11 @@
+use crate::bve::unit::parse::{split_quantity, ParseQuantityError};
 use crate::bve::unit::{Time, Velocity};
-use num_traits::{cast, AsPrimitive, Num, NumCast};
-use std::fmt::{Debug, Formatter};
-use std::ops::{Add, Mul, Sub};
+use num_traits::{cast, Num, NumCast};
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+const UNITS: &[&str] = &["km/h/s", "m/s2", "m/s²"];
 
 #[derive(Copy, Clone, PartialOrd, PartialEq)]
 pub struct Acceleration(/*meter per second per second*/ pub(super) f64);
@@ -18,29 +22,64 @@ impl Acceleration {
     {
         Self::kilometer_per_second_per_second(value)
     }
-    pub fn kilometer_per_second_per_hour<T>(value: T) -> Self
+    // km/h gained per second, the unit train performance is quoted in
+    pub fn kilometer_per_hour_per_second<T>(value: T) -> Self
     where
         T: Into<f64>,
     {
         Self(value.into() * (1000. / 3600.))
     }
-    pub fn kmpsh<T>(value: T) -> Self
+    pub fn kmphps<T>(value: T) -> Self
+    where
+        T: Into<f64>,
+    {
+        Self::kilometer_per_hour_per_second(value)
+    }
+    pub fn meter_per_second_per_second<T>(value: T) -> Self
     where
         T: Into<f64>,
     {
-        Self::kilometer_per_second_per_hour(value)
+        Self(value.into())
+    }
+    pub fn mps2<T>(value: T) -> Self
+    where
+        T: Into<f64>,
+    {
+        Self::meter_per_second_per_second(value)
+    }
+    pub fn as_mps2(&self) -> f64 {
+        self.0
     }
     pub fn as_kmps2(&self) -> f64 {
         self.0 / 1000.
     }
-    pub fn as_kmpsh(&self) -> f64 {
+    pub fn as_kmphps(&self) -> f64 {
         self.0 * (3600. / 1000.)
     }
 }
 
 impl Debug for Acceleration {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}km/h/s", self.as_kmps2())
+        write!(f, "{:?}km/h/s", self.as_kmphps())
+    }
+}
+// In km/h/s; the precision, if any, applies to the number
+impl Display for Acceleration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.as_kmphps(), f)?;
+        f.write_str("km/h/s")
+    }
+}
+// Accepts km/h/s and m/s², e.g. "2.5km/h/s"
+impl FromStr for Acceleration {
+    type Err = ParseQuantityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (value, unit) = split_quantity(s, UNITS)?;
+        Ok(match unit {
+            "km/h/s" => Acceleration::kilometer_per_hour_per_second(value),
+            _ => Acceleration::meter_per_second_per_second(value),
+        })
     }
 }
 impl Add for Acceleration {
@@ -57,6 +96,27 @@ impl Sub for Acceleration {
         Self(self.0 - rhs.0)
     }
 }
+impl Mul<f64> for Acceleration {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Self(self.0 * rhs)
+    }
+}
+impl Div<f64> for Acceleration {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        Self(self.0 / rhs)
+    }
+}
+impl Neg for Acceleration {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
 impl<T> Mul<Time<T>> for Acceleration
 where
     T: 'static + Num + Copy + NumCast + PartialOrd,
@@ -83,20 +143,39 @@ mod tests {
     fn construct() {
         assert_eq!(
             0.0,
-            Acceleration::kilometer_per_second_per_hour(0.0).as_kmpsh()
+            Acceleration::kilometer_per_hour_per_second(0.0).as_kmphps()
         );
         assert_eq!(
             1.,
-            Acceleration::kilometer_per_second_per_hour(1.0).as_kmpsh()
+            Acceleration::kilometer_per_hour_per_second(1.0).as_kmphps()
         );
+        assert_eq!(2.5, Acceleration::kmphps(2.5).as_kmphps());
+        assert_eq!(1., Acceleration::kmphps(3.6).as_mps2());
         assert_eq!(0.5, Acceleration::kmps2(0.5).as_kmps2());
-        assert_eq!(0.5, Acceleration::kmpsh(1800.).as_kmps2());
+        assert_eq!(0.5, Acceleration::kmphps(1800.).as_kmps2());
+        assert_eq!(0.5, Acceleration::mps2(0.5).as_mps2());
+        assert_eq!(1000., Acceleration::kmps2(1).as_mps2());
     }
     #[test]
     fn unit() {
-        assert_eq!(Acceleration::kmpsh(1800.), Acceleration::kmps2(0.5));
-        assert_eq!(Acceleration::kmpsh(3600.), Acceleration::kmps2(1.));
-        assert_eq!(Acceleration::kmps2(1.), Acceleration::kmpsh(3600.));
+        assert_eq!(Acceleration::kmphps(1800.), Acceleration::kmps2(0.5));
+        assert_eq!(Acceleration::kmphps(3600.), Acceleration::kmps2(1.));
+        assert_eq!(Acceleration::kmps2(1.), Acceleration::kmphps(3600.));
+    }
+    #[test]
+    fn parse() {
+        assert_eq!(Ok(Acceleration::kmphps(2.5)), "2.5km/h/s".parse());
+        assert_eq!(Ok(Acceleration::mps2(0.8)), "0.8 m/s2".parse());
+        assert_eq!(Ok(Acceleration::mps2(0.8)), "0.8m/s²".parse());
+        assert_eq!(
+            Err(ParseQuantityError::InvalidNumber("".to_owned())),
+            "fast".parse::<Acceleration>()
+        );
+    }
+    #[test]
+    fn format() {
+        assert_eq!("3.6km/h/s", Acceleration::mps2(1.).to_string());
+        assert_eq!("3.6km/h/s", format!("{:?}", Acceleration::mps2(1.)));
     }
     #[test]
     fn add() {
@@ -105,8 +184,8 @@ mod tests {
             Acceleration::kmps2(3) + Acceleration::kmps2(1)
         );
         assert_eq!(
-            Acceleration::kmpsh(1.5),
-            Acceleration::kmpsh(1.) + Acceleration::kmpsh(0.5)
+            Acceleration::kmphps(1.5),
+            Acceleration::kmphps(1.) + Acceleration::kmphps(0.5)
         );
     }
     #[test]
@@ -116,16 +195,33 @@ mod tests {
             Acceleration::kmps2(3) - Acceleration::kmps2(1)
         );
         assert_eq!(
-            Acceleration::kmpsh(0.5),
-            Acceleration::kmpsh(1.) - Acceleration::kmpsh(0.5)
+            Acceleration::kmphps(0.5),
+            Acceleration::kmphps(1.) - Acceleration::kmphps(0.5)
         );
     }
     #[test]
+    fn scalar() {
+        assert_eq!(Acceleration::mps2(3.), Acceleration::mps2(1.5) * 2.);
+        assert_eq!(Acceleration::mps2(0.5), Acceleration::mps2(2.) / 4.);
+        assert_eq!(Acceleration::mps2(-1.), -Acceleration::mps2(1.));
+    }
+    #[test]
     fn mul() {
-        assert_eq!(Velocity::kmps(1), Acceleration::kmpsh(5) * Time::hours(0.2));
+        assert_eq!(
+            Velocity::kmps(1),
+            Acceleration::kmphps(5) * Time::hours(0.2)
+        );
         assert_eq!(
             Velocity::kmps(1),
             Time::seconds(0.2) * Acceleration::kmps2(5)
         );
+        assert_eq!(
+            Velocity::mps(1.),
+            Acceleration::mps2(2.) * Time::milliseconds(500)
+        );
+        assert_eq!(
+            Velocity::kmph(25.),
+            Acceleration::kmphps(2.5) * Time::seconds(10)
+        );
     }
 }
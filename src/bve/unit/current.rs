@@ -0,0 +1,75 @@
+use std::ffi::c_float;
+use std::fmt::{Debug, Formatter};
+use std::ops::{Add, Neg, Sub};
+
+// Motor current: positive while powering, negative while regenerating
+#[repr(C)]
+#[derive(Copy, Clone, PartialOrd, PartialEq)]
+pub struct ElectricCurrent(/* ampere */ pub(super) c_float);
+impl ElectricCurrent {
+    pub fn amperes<T>(value: T) -> Self
+    where
+        T: Into<f64>,
+    {
+        Self(value.into() as c_float)
+    }
+    pub fn as_amperes(&self) -> f64 {
+        self.0 as f64
+    }
+    pub fn is_powering(&self) -> bool {
+        self.0 > 0.
+    }
+    pub fn is_regenerating(&self) -> bool {
+        self.0 < 0.
+    }
+    // Current regardless of its direction
+    pub fn magnitude(&self) -> Self {
+        Self(self.0.abs())
+    }
+}
+
+impl Debug for ElectricCurrent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}A", self.as_amperes())
+    }
+}
+impl Add for ElectricCurrent {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+impl Sub for ElectricCurrent {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+impl Neg for ElectricCurrent {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn direction() {
+        let powering = ElectricCurrent::amperes(350);
+        let regenerating = ElectricCurrent::amperes(-200);
+        assert!(powering.is_powering());
+        assert!(!powering.is_regenerating());
+        assert!(regenerating.is_regenerating());
+        assert!(!ElectricCurrent::amperes(0).is_powering());
+        assert!(!ElectricCurrent::amperes(0).is_regenerating());
+        assert_eq!(ElectricCurrent::amperes(200), regenerating.magnitude());
+        assert_eq!(regenerating, -ElectricCurrent::amperes(200));
+        assert_eq!(ElectricCurrent::amperes(150), powering + regenerating);
+        assert_eq!(ElectricCurrent::amperes(550), powering - regenerating);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::bve::unit::{Acceleration, Length, Time, Velocity};
+use num_traits::{cast, Num, NumCast};
+
+// Constant-deceleration motion. Results are in floating point whatever the input types.
+impl Velocity {
+    // Distance needed to stop from this speed: v² / 2a
+    pub fn braking_distance(self, deceleration: Acceleration) -> Length<f64> {
+        Length::meters(self.0 * self.0 / (2. * deceleration.0))
+    }
+    // Speed left after `distance`: √(v² - 2ad), or zero once the train would have stopped.
+    // A negative deceleration gives the speed the train needs to have `distance` before
+    // reaching this one, as in a braking curve.
+    pub fn speed_at_distance<T>(self, deceleration: Acceleration, distance: Length<T>) -> Velocity
+    where
+        T: 'static + Num + Copy + NumCast + PartialOrd,
+    {
+        let meters = cast::<T, f64>(distance.0).unwrap() / 1000.;
+        Velocity(
+            (self.0 * self.0 - 2. * deceleration.0 * meters)
+                .max(0.)
+                .sqrt(),
+        )
+    }
+    // v / a
+    pub fn time_to_stop(self, deceleration: Acceleration) -> Time<f64> {
+        Time::seconds(self.0 / deceleration.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn braking_distance() {
+        let deceleration = Acceleration::mps2(1.);
+        assert_eq!(
+            Length::meters(200.),
+            Velocity::mps(20.).braking_distance(deceleration)
+        );
+        assert_eq!(
+            Length::meters(125.),
+            Velocity::kmph(90.).braking_distance(Acceleration::kmphps(9.))
+        );
+    }
+    #[test]
+    fn speed_at_distance() {
+        let deceleration = Acceleration::mps2(1.);
+        assert_eq!(
+            Velocity::mps(10.),
+            Velocity::mps(20.).speed_at_distance(deceleration, Length::meters(150))
+        );
+        assert_eq!(
+            Velocity::mps(0.),
+            Velocity::mps(20.).speed_at_distance(deceleration, Length::meters(250.))
+        );
+        assert_eq!(
+            Velocity::mps(13.),
+            Velocity::mps(5.).speed_at_distance(-deceleration, Length::meters(72.))
+        );
+    }
+    #[test]
+    fn time_to_stop() {
+        assert_eq!(
+            Time::seconds(20.),
+            Velocity::mps(20.).time_to_stop(Acceleration::mps2(1.))
+        );
+        assert_eq!(
+            Time::seconds(36.),
+            Velocity::kmph(90.).time_to_stop(Acceleration::kmphps(2.5))
+        );
+    }
+}
@@ -1,8 +1,12 @@
+use crate::bve::unit::parse::{split_quantity, to_raw, ParseQuantityError};
 use crate::bve::unit::velocity::Velocity;
 use crate::bve::unit::Time;
 use num_traits::{cast, AsPrimitive, Num, NumCast};
-use std::fmt::{Debug, Formatter};
-use std::ops::{Add, Div, Sub};
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+const UNITS: &[&str] = &["mm", "m", "km"];
 
 #[repr(C)]
 #[derive(Copy, Clone, PartialOrd, PartialEq)]
@@ -23,7 +27,7 @@ where
         Length(value * Self::thousand() * Self::thousand())
     }
     pub fn meters(value: T) -> Length<T> {
-        Length(value * Self::thousand())
+        Length(value * Self::raw_to_meter())
     }
     pub fn millimeters(value: T) -> Length<T> {
         Length(value)
@@ -32,7 +36,7 @@ where
         self.0 / Self::thousand() / Self::thousand()
     }
     pub fn as_meters(&self) -> T {
-        self.0 / Self::thousand()
+        self.0 / Self::raw_to_meter()
     }
     pub fn as_millimeters(&self) -> T {
         self.0
@@ -44,12 +48,48 @@ where
         Length(cast(self.0).unwrap())
     }
 }
+impl<T> Length<T>
+where
+    T: 'static + Num + Copy + NumCast + PartialOrd,
+{
+    // Meters without the rounding of integer `as_meters()`
+    fn to_meters_f64(self) -> f64 {
+        cast::<T, f64>(self.0).unwrap() / 1000.
+    }
+}
 impl<T> Debug for Length<T>
 where
-    T: 'static + Num + Copy + NumCast + PartialOrd + Debug,
+    T: 'static + Num + Copy + NumCast + PartialOrd,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}m", self.to_meters_f64())
+    }
+}
+// In meters; the precision, if any, applies to the number
+impl<T> Display for Length<T>
+where
+    T: 'static + Num + Copy + NumCast + PartialOrd,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}m", self.as_meters())
+        Display::fmt(&self.to_meters_f64(), f)?;
+        f.write_str("m")
+    }
+}
+// Accepts mm, m and km, e.g. "120m" or "1.2 km"
+impl<T> FromStr for Length<T>
+where
+    T: 'static + Num + Copy + NumCast + PartialOrd,
+{
+    type Err = ParseQuantityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (value, unit) = split_quantity(s, UNITS)?;
+        let millimeters = match unit {
+            "mm" => value,
+            "m" => value * 1000.,
+            _ => value * 1_000_000.,
+        };
+        to_raw(millimeters, "mm").map(Length)
     }
 }
 
@@ -73,6 +113,47 @@ where
         Self(self.0 - rhs.0)
     }
 }
+impl<T> Mul<T> for Length<T>
+where
+    T: 'static + Num + Copy + NumCast + PartialOrd,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self(self.0 * rhs)
+    }
+}
+impl<T> Div<T> for Length<T>
+where
+    T: 'static + Num + Copy + NumCast + PartialOrd,
+{
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self::Output {
+        Self(self.0 / rhs)
+    }
+}
+impl<T> Neg for Length<T>
+where
+    T: 'static + Num + Copy + NumCast + PartialOrd + Neg<Output = T>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+// mm / (m/s) = ms; always in floating point, as the time is infinite at zero speed
+impl<T> Div<Velocity> for Length<T>
+where
+    T: 'static + Num + Copy + NumCast + PartialOrd,
+{
+    type Output = Time<f64>;
+
+    fn div(self, rhs: Velocity) -> Self::Output {
+        Time(cast::<T, f64>(self.0).unwrap() / rhs.0)
+    }
+}
 impl<T> Div<Time<T>> for Length<T>
 where
     T: 'static + Num + Copy + NumCast + PartialOrd,
@@ -131,6 +212,50 @@ mod tests {
         assert_eq!(Length::meters(1), Length::millimeters(1_000));
     }
 
+    #[test]
+    fn parse() {
+        assert_eq!(Ok(Length::meters(120)), "120m".parse());
+        assert_eq!(Ok(Length::meters(1200.)), "1.2km".parse());
+        assert_eq!(Ok(Length::millimeters(5)), "5 mm".parse());
+        assert_eq!(Ok(Length::millimeters(1500)), "1.5m".parse::<Length<i32>>());
+        assert_eq!(
+            Err(ParseQuantityError::OutOfRange),
+            "-1m".parse::<Length<u32>>()
+        );
+        assert_eq!(
+            Err(ParseQuantityError::MissingUnit),
+            "120".parse::<Length<f64>>()
+        );
+        assert_eq!(
+            Err(ParseQuantityError::Inexact { resolution: "mm" }),
+            "1.5mm".parse::<Length<i32>>()
+        );
+        assert_eq!(Ok(Length::millimeters(1.5)), "1.5mm".parse::<Length<f64>>());
+    }
+    #[test]
+    fn format() {
+        assert_eq!("1.5m", Length::millimeters(1500).to_string());
+        assert_eq!("1.5m", format!("{:?}", Length::millimeters(1500)));
+        assert_eq!("120.0m", format!("{:.1}", Length::meters(120.)));
+    }
+    #[test]
+    fn scalar() {
+        assert_eq!(Length::meters(300), Length::meters(100) * 3);
+        assert_eq!(Length::meters(2.5), Length::meters(10.) / 4.);
+        assert_eq!(Length::meters(-5), -Length::meters(5));
+    }
+    #[test]
+    fn divide_by_velocity() {
+        assert_eq!(Time::seconds(8.), Length::meters(100) / Velocity::mps(12.5));
+        assert_eq!(
+            Time::seconds(36.),
+            Length::kilometers(0.5) / Velocity::kmph(50)
+        );
+        assert_eq!(
+            Time::milliseconds(f64::INFINITY),
+            Length::meters(1) / Velocity::mps(0.)
+        );
+    }
     #[test]
     fn add() {
         assert_eq!(
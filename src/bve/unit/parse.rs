@@ -0,0 +1,127 @@
+use num_traits::{cast, NumCast};
+use std::fmt::{Display, Formatter};
+
+// Leeway for the rounding of unit conversions, e.g. 2.01s is 2009.9999999999998ms
+const WHOLE_TOLERANCE: f64 = 1e-6;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum ParseQuantityError {
+    // The text does not start with a decimal number
+    InvalidNumber(String),
+    MissingUnit,
+    UnknownUnit {
+        unit: String,
+        expected: &'static [&'static str],
+    },
+    // Valid, but does not fit in the raw representation
+    OutOfRange,
+    // Finer than the integer raw representation, whose unit is given
+    Inexact {
+        resolution: &'static str,
+    },
+}
+
+impl Display for ParseQuantityError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseQuantityError::InvalidNumber(text) => write!(f, "`{text}` is not a number"),
+            ParseQuantityError::MissingUnit => write!(f, "missing unit"),
+            ParseQuantityError::UnknownUnit { unit, expected } => write!(
+                f,
+                "unknown unit `{unit}`, expected one of {}",
+                expected.join(", ")
+            ),
+            ParseQuantityError::OutOfRange => write!(f, "out of range"),
+            ParseQuantityError::Inexact { resolution } => {
+                write!(f, "finer than the resolution of 1{resolution}")
+            }
+        }
+    }
+}
+
+// Splits "2.5 km/h/s" into the number and one of `units`, which is returned as listed
+pub(super) fn split_quantity(
+    text: &str,
+    units: &'static [&'static str],
+) -> Result<(f64, &'static str), ParseQuantityError> {
+    let text = text.trim();
+    let end = text
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '+' | '-' | '.')))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(end);
+    let value = number
+        .parse::<f64>()
+        .map_err(|_| ParseQuantityError::InvalidNumber(number.to_owned()))?;
+    let unit = unit.trim_start();
+    if unit.is_empty() {
+        return Err(ParseQuantityError::MissingUnit);
+    }
+    let unit = units.iter().find(|&&known| known == unit).ok_or_else(|| {
+        ParseQuantityError::UnknownUnit {
+            unit: unit.to_owned(),
+            expected: units,
+        }
+    })?;
+    Ok((value, unit))
+}
+
+// Raw value from a quantity already scaled to the raw unit. An integer representation takes
+// whole raw units only, rather than truncating.
+pub(super) fn to_raw<T: NumCast>(
+    value: f64,
+    resolution: &'static str,
+) -> Result<T, ParseQuantityError> {
+    let integer = cast::<f64, T>(0.5).and_then(cast::<T, f64>) == Some(0.);
+    let value = if integer {
+        let whole = value.round();
+        if (value - whole).abs() > WHOLE_TOLERANCE {
+            return Err(ParseQuantityError::Inexact { resolution });
+        }
+        whole
+    } else {
+        value
+    };
+    cast(value).ok_or(ParseQuantityError::OutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNITS: &[&str] = &["m", "km"];
+
+    #[test]
+    fn split() {
+        assert_eq!(Ok((1.2, "km")), split_quantity("1.2km", UNITS));
+        assert_eq!(Ok((-5., "m")), split_quantity(" -5 m ", UNITS));
+        assert_eq!(
+            Err(ParseQuantityError::InvalidNumber("".to_owned())),
+            split_quantity("km", UNITS)
+        );
+        assert_eq!(
+            Err(ParseQuantityError::InvalidNumber("1.2.3".to_owned())),
+            split_quantity("1.2.3m", UNITS)
+        );
+        assert_eq!(
+            Err(ParseQuantityError::MissingUnit),
+            split_quantity("120", UNITS)
+        );
+        let error = split_quantity("120 ft", UNITS).unwrap_err();
+        assert_eq!(
+            "unknown unit `ft`, expected one of m, km",
+            error.to_string()
+        );
+    }
+    #[test]
+    fn raw() {
+        assert_eq!(Ok(2010), to_raw::<i32>(2.01 * 1000., "ms"));
+        assert_eq!(Ok(0.5), to_raw::<f32>(0.5, "mm"));
+        let error = to_raw::<i32>(1.5, "mm").unwrap_err();
+        assert_eq!(ParseQuantityError::Inexact { resolution: "mm" }, error);
+        assert_eq!("finer than the resolution of 1mm", error.to_string());
+        assert_eq!(
+            Err(ParseQuantityError::OutOfRange),
+            to_raw::<u32>(-1000., "mm")
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use crate::bve::unit::parse::{split_quantity, ParseQuantityError};
+use crate::bve::unit::Time;
+use num_traits::{cast, Num, NumCast};
+use std::ffi::c_float;
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+const UNITS: &[&str] = &["kPa", "MPa", "kgf/cm2", "kgf/cm²"];
+// Standard gravity; 1 kgf/cm² = 98.0665 kPa
+const KPA_PER_KGF_PER_CM2: f64 = 98.0665;
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialOrd, PartialEq)]
+pub struct Pressure(/* kilopascal */ pub(super) c_float);
+impl Pressure {
+    pub fn kilopascals<T>(value: T) -> Self
+    where
+        T: Into<f64>,
+    {
+        Self(value.into() as c_float)
+    }
+    pub fn kpa<T>(value: T) -> Self
+    where
+        T: Into<f64>,
+    {
+        Self::kilopascals(value)
+    }
+    pub fn megapascals<T>(value: T) -> Self
+    where
+        T: Into<f64>,
+    {
+        Self::kilopascals(value.into() * 1000.)
+    }
+    pub fn mpa<T>(value: T) -> Self
+    where
+        T: Into<f64>,
+    {
+        Self::megapascals(value)
+    }
+    // The unit of older Japanese brake gauges
+    pub fn kilogram_force_per_square_centimeter<T>(value: T) -> Self
+    where
+        T: Into<f64>,
+    {
+        Self::kilopascals(value.into() * KPA_PER_KGF_PER_CM2)
+    }
+    pub fn kgf_per_cm2<T>(value: T) -> Self
+    where
+        T: Into<f64>,
+    {
+        Self::kilogram_force_per_square_centimeter(value)
+    }
+    pub fn as_kpa(&self) -> f64 {
+        self.0 as f64
+    }
+    pub fn as_mpa(&self) -> f64 {
+        self.as_kpa() / 1000.
+    }
+    pub fn as_kgf_per_cm2(&self) -> f64 {
+        self.as_kpa() / KPA_PER_KGF_PER_CM2
+    }
+}
+
+// Rate of pressure change, e.g. how fast the brake cylinder fills
+#[derive(Copy, Clone, PartialOrd, PartialEq)]
+pub struct PressureRate(/* kilopascal per second */ f64);
+impl PressureRate {
+    pub fn kilopascals_per_second<T>(value: T) -> Self
+    where
+        T: Into<f64>,
+    {
+        Self(value.into())
+    }
+    pub fn kpaps<T>(value: T) -> Self
+    where
+        T: Into<f64>,
+    {
+        Self::kilopascals_per_second(value)
+    }
+    pub fn as_kpaps(&self) -> f64 {
+        self.0
+    }
+}
+
+impl Debug for Pressure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}kPa", self.as_kpa())
+    }
+}
+// In kPa; the precision, if any, applies to the number
+impl Display for Pressure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.as_kpa(), f)?;
+        f.write_str("kPa")
+    }
+}
+// Accepts kPa, MPa and kgf/cm², e.g. "390kPa" or "3.5kgf/cm2"
+impl FromStr for Pressure {
+    type Err = ParseQuantityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (value, unit) = split_quantity(s, UNITS)?;
+        Ok(match unit {
+            "kPa" => Pressure::kilopascals(value),
+            "MPa" => Pressure::megapascals(value),
+            _ => Pressure::kilogram_force_per_square_centimeter(value),
+        })
+    }
+}
+impl Debug for PressureRate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}kPa/s", self.as_kpaps())
+    }
+}
+impl Add for Pressure {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+impl Sub for Pressure {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+impl Mul<f64> for Pressure {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Self::kilopascals(self.as_kpa() * rhs)
+    }
+}
+impl Div<f64> for Pressure {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        Self::kilopascals(self.as_kpa() / rhs)
+    }
+}
+impl Neg for Pressure {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+// kPa / ms, scaled to kPa/s
+impl<T> Div<Time<T>> for Pressure
+where
+    T: 'static + Num + Copy + NumCast + PartialOrd,
+{
+    type Output = PressureRate;
+
+    fn div(self, rhs: Time<T>) -> Self::Output {
+        PressureRate(self.as_kpa() * 1000. / cast::<T, f64>(rhs.0).unwrap())
+    }
+}
+impl<T> Mul<Time<T>> for PressureRate
+where
+    T: 'static + Num + Copy + NumCast + PartialOrd,
+{
+    type Output = Pressure;
+
+    fn mul(self, rhs: Time<T>) -> Self::Output {
+        Pressure::kilopascals(self.0 * cast::<T, f64>(rhs.0).unwrap() / 1000.)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn construct() {
+        assert_eq!(490., Pressure::kpa(490).as_kpa());
+        assert_eq!(Pressure::kilopascals(0.5), Pressure::kpa(0.5));
+        assert_eq!(Pressure::kpa(880), Pressure::mpa(0.88));
+        assert_eq!(0.5, Pressure::kpa(500).as_mpa());
+        assert_eq!(Pressure::kpa(98.0665), Pressure::kgf_per_cm2(1));
+        assert!((Pressure::kpa(490).as_kgf_per_cm2() - 4.9966).abs() < 1e-4);
+    }
+    #[test]
+    fn compare() {
+        assert!(Pressure::kpa(400) > Pressure::kgf_per_cm2(4));
+        assert!(Pressure::mpa(0.39) < Pressure::kpa(400));
+    }
+    #[test]
+    fn arithmetic() {
+        assert_eq!(Pressure::kpa(500), Pressure::kpa(300) + Pressure::kpa(200));
+        assert_eq!(Pressure::kpa(100), Pressure::kpa(300) - Pressure::kpa(200));
+        assert_eq!(Pressure::kpa(150), Pressure::kpa(300) * 0.5);
+        assert_eq!(Pressure::kpa(100), Pressure::kpa(300) / 3.);
+        assert_eq!(Pressure::kpa(-5), -Pressure::kpa(5));
+    }
+    #[test]
+    fn rate() {
+        assert_eq!(
+            PressureRate::kpaps(100),
+            Pressure::kpa(300) / Time::seconds(3)
+        );
+        assert_eq!(
+            PressureRate::kpaps(200),
+            Pressure::kpa(100) / Time::milliseconds(500)
+        );
+        assert_eq!(
+            Pressure::kpa(150),
+            PressureRate::kpaps(100) * Time::milliseconds(1500)
+        );
+    }
+    #[test]
+    fn parse() {
+        assert_eq!(Ok(Pressure::kpa(390)), "390kPa".parse());
+        assert_eq!(Ok(Pressure::kpa(880)), "0.88 MPa".parse());
+        assert_eq!(Ok(Pressure::kgf_per_cm2(3.5)), "3.5kgf/cm2".parse());
+        assert_eq!("390kPa", Pressure::kpa(390).to_string());
+    }
+}
@@ -1,8 +1,13 @@
+use crate::bve::unit::parse::{split_quantity, to_raw, ParseQuantityError};
 use num_traits::{cast, AsPrimitive, Num, NumCast};
-use std::ops::{Add, Sub};
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+const UNITS: &[&str] = &["ms", "s", "min", "h"];
 
 #[repr(C)]
-#[derive(Copy, Clone, Debug, PartialOrd, PartialEq)]
+#[derive(Copy, Clone, PartialOrd, PartialEq)]
 pub struct Time<T>(pub(super) T)
 where
     T: 'static + Num + Copy + NumCast + PartialOrd;
@@ -39,6 +44,51 @@ where
         Time(cast(self.0).unwrap())
     }
 }
+impl<T> Time<T>
+where
+    T: 'static + Num + Copy + NumCast + PartialOrd,
+{
+    // Seconds without the rounding of integer `as_seconds()`
+    fn to_seconds_f64(self) -> f64 {
+        cast::<T, f64>(self.0).unwrap() / 1000.
+    }
+}
+impl<T> Debug for Time<T>
+where
+    T: 'static + Num + Copy + NumCast + PartialOrd,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}s", self.to_seconds_f64())
+    }
+}
+// In seconds; the precision, if any, applies to the number
+impl<T> Display for Time<T>
+where
+    T: 'static + Num + Copy + NumCast + PartialOrd,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.to_seconds_f64(), f)?;
+        f.write_str("s")
+    }
+}
+// Accepts ms, s, min and h, e.g. "3s" or "1.5 min"
+impl<T> FromStr for Time<T>
+where
+    T: 'static + Num + Copy + NumCast + PartialOrd,
+{
+    type Err = ParseQuantityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (value, unit) = split_quantity(s, UNITS)?;
+        let milliseconds = match unit {
+            "ms" => value,
+            "s" => value * 1000.,
+            "min" => value * 60. * 1000.,
+            _ => value * 60. * 60. * 1000.,
+        };
+        to_raw(milliseconds, "ms").map(Time)
+    }
+}
 impl<T> Add for Time<T>
 where
     T: 'static + Num + Copy + NumCast + PartialOrd,
@@ -59,6 +109,36 @@ where
         Self(self.0 - rhs.0)
     }
 }
+impl<T> Mul<T> for Time<T>
+where
+    T: 'static + Num + Copy + NumCast + PartialOrd,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self(self.0 * rhs)
+    }
+}
+impl<T> Div<T> for Time<T>
+where
+    T: 'static + Num + Copy + NumCast + PartialOrd,
+{
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self::Output {
+        Self(self.0 / rhs)
+    }
+}
+impl<T> Neg for Time<T>
+where
+    T: 'static + Num + Copy + NumCast + PartialOrd + Neg<Output = T>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,6 +169,37 @@ mod tests {
         assert_eq!(Time::milliseconds(100), Time::milliseconds(100).as_());
     }
     #[test]
+    fn parse() {
+        assert_eq!(Ok(Time::seconds(3)), "3s".parse());
+        assert_eq!(Ok(Time::milliseconds(250.)), "250ms".parse());
+        assert_eq!(Ok(Time::seconds(90)), "1.5 min".parse());
+        assert_eq!(Ok(Time::hours(2.)), "2h".parse());
+        assert_eq!(
+            Err(ParseQuantityError::UnknownUnit {
+                unit: "sec".to_owned(),
+                expected: UNITS
+            }),
+            "3sec".parse::<Time<i32>>()
+        );
+        assert_eq!(Ok(Time::milliseconds(2010)), "2.01s".parse());
+        assert_eq!(
+            Err(ParseQuantityError::Inexact { resolution: "ms" }),
+            "0.0005s".parse::<Time<i32>>()
+        );
+    }
+    #[test]
+    fn format() {
+        assert_eq!("1.5s", Time::milliseconds(1500).to_string());
+        assert_eq!("1.5s", format!("{:?}", Time::milliseconds(1500)));
+        assert_eq!("3.00s", format!("{:.2}", Time::seconds(3.)));
+    }
+    #[test]
+    fn scalar() {
+        assert_eq!(Time::seconds(6), Time::seconds(2) * 3);
+        assert_eq!(Time::milliseconds(500.), Time::seconds(2.) / 4.);
+        assert_eq!(Time::seconds(-3), -Time::seconds(3));
+    }
+    #[test]
     fn add() {
         assert_eq!(Time::seconds(35), Time::seconds(10) + Time::seconds(25));
         assert_eq!(Time::minutes(1.), Time::seconds(20.) + Time::seconds(40.))
@@ -1,10 +1,15 @@
 use crate::bve::unit::acceleration::Acceleration;
+use crate::bve::unit::parse::{split_quantity, ParseQuantityError};
 use crate::bve::unit::{Length, Time};
-use num_traits::{cast, AsPrimitive, Float, Num, NumCast};
-use std::ops::{Add, Div, Mul, Sub};
+use num_traits::{cast, Num, NumCast};
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+const UNITS: &[&str] = &["km/h", "m/s"];
 
 #[repr(C)]
-#[derive(Copy, Clone, Debug, PartialOrd, PartialEq)]
+#[derive(Copy, Clone, PartialOrd, PartialEq)]
 pub struct Velocity(/* meter per second */ pub(super) f64);
 impl Velocity {
     pub fn kilometer_per_second<T>(value: T) -> Self
@@ -19,6 +24,18 @@ impl Velocity {
     {
         Self::kilometer_per_second(value)
     }
+    pub fn kilometer_per_hour<T>(value: T) -> Self
+    where
+        T: Into<f64>,
+    {
+        Self(value.into() / 3.6)
+    }
+    pub fn kmph<T>(value: T) -> Self
+    where
+        T: Into<f64>,
+    {
+        Self::kilometer_per_hour(value)
+    }
     pub fn meter_per_second<T>(value: T) -> Self
     where
         T: Into<f64>,
@@ -37,8 +54,35 @@ impl Velocity {
     pub fn as_kmps(&self) -> f64 {
         self.0 / 1000f64
     }
+    pub fn as_kmph(&self) -> f64 {
+        self.0 * 3.6
+    }
 }
 
+impl Debug for Velocity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}km/h", self.as_kmph())
+    }
+}
+// In km/h; the precision, if any, applies to the number
+impl Display for Velocity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.as_kmph(), f)?;
+        f.write_str("km/h")
+    }
+}
+// Accepts km/h and m/s, e.g. "45km/h"
+impl FromStr for Velocity {
+    type Err = ParseQuantityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (value, unit) = split_quantity(s, UNITS)?;
+        Ok(match unit {
+            "km/h" => Velocity::kilometer_per_hour(value),
+            _ => Velocity::meter_per_second(value),
+        })
+    }
+}
 impl Add for Velocity {
     type Output = Self;
 
@@ -53,14 +97,36 @@ impl Sub for Velocity {
         Self(self.0 - rhs.0)
     }
 }
+impl Mul<f64> for Velocity {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Self(self.0 * rhs)
+    }
+}
+impl Div<f64> for Velocity {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        Self(self.0 / rhs)
+    }
+}
+impl Neg for Velocity {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
 impl<T> Mul<Time<T>> for Velocity
 where
     T: 'static + Num + Copy + NumCast + PartialOrd,
 {
     type Output = Length<T>;
 
+    // m/s * ms = mm; multiplied before casting so that a fractional speed is kept
     fn mul(self, rhs: Time<T>) -> Self::Output {
-        Length(cast::<f64, T>(self.0).unwrap() * rhs.0)
+        Length(cast(self.0 * cast::<T, f64>(rhs.0).unwrap()).unwrap())
     }
 }
 impl<T> Mul<Velocity> for Time<T>
@@ -78,8 +144,9 @@ where
     T: 'static + Num + Copy + NumCast + PartialOrd,
 {
     type Output = Acceleration;
+    // Not through `as_seconds()`, which truncates for integer times
     fn div(self, rhs: Time<T>) -> Self::Output {
-        Acceleration(self.0 / cast::<T, f64>(rhs.as_seconds()).unwrap())
+        Acceleration(self.0 * 1000. / cast::<T, f64>(rhs.0).unwrap())
     }
 }
 #[cfg(test)]
@@ -98,6 +165,36 @@ mod tests {
         assert_eq!(10., Velocity::kmps(10.).as_kmps());
     }
     #[test]
+    fn kilometer_per_hour() {
+        assert!(nearly_equal(
+            10.,
+            Velocity::kilometer_per_hour(36.).as_mps()
+        ));
+        assert_eq!(Velocity::mps(0.), Velocity::kilometer_per_hour(0.));
+        assert_eq!(Velocity::mps(12.5), Velocity::kmph(45));
+        assert_eq!(45., Velocity::mps(12.5).as_kmph());
+        assert!(nearly_equal(25., Velocity::kmph(25.).as_kmph()));
+        assert_eq!(3.6, Velocity::kmps(0.001).as_kmph());
+    }
+    #[test]
+    fn parse() {
+        assert_eq!(Ok(Velocity::kilometer_per_hour(45)), "45km/h".parse());
+        assert_eq!(Ok(Velocity::mps(12.5)), "12.5 m/s".parse());
+        assert_eq!(
+            Err(ParseQuantityError::UnknownUnit {
+                unit: "kph".to_owned(),
+                expected: UNITS
+            }),
+            "45kph".parse::<Velocity>()
+        );
+    }
+    #[test]
+    fn format() {
+        assert_eq!("45km/h", Velocity::mps(12.5).to_string());
+        assert_eq!("45.0km/h", format!("{:?}", Velocity::mps(12.5)));
+        assert_eq!("25km/h", format!("{:.0}", Velocity::kilometer_per_hour(25)));
+    }
+    #[test]
     fn add() {
         assert_eq!(Velocity::mps(12), Velocity::mps(5) + Velocity::mps(7));
         assert_eq!(Velocity::mps(8.), Velocity::mps(5.) + Velocity::mps(3.));
@@ -108,6 +205,12 @@ mod tests {
         assert_eq!(Velocity::mps(-2.), Velocity::mps(5.) - Velocity::mps(7.));
     }
     #[test]
+    fn scalar() {
+        assert_eq!(Velocity::kmph(90.), Velocity::kmph(45.) * 2.);
+        assert_eq!(Velocity::mps(2.5), Velocity::mps(10.) / 4.);
+        assert_eq!(Velocity::mps(-3.), -Velocity::mps(3.));
+    }
+    #[test]
     fn multiply() {
         assert_eq!(Length::meters(14), Velocity::mps(7) * Time::seconds(2));
         assert_eq!(Length::meters(15.), Velocity::mps(7.5) * Time::seconds(2.));
@@ -117,6 +220,12 @@ mod tests {
         );
 
         assert_eq!(Length::meters(14), Time::seconds(2) * Velocity::mps(7),);
+        // Fractional speed with an integer time
+        assert_eq!(Length::meters(15), Velocity::mps(7.5) * Time::seconds(2));
+        assert_eq!(
+            Length::millimeters(1250),
+            Velocity::kmph(9) * Time::milliseconds(500)
+        );
     }
     #[test]
     fn divide() {
@@ -126,5 +235,22 @@ mod tests {
             Velocity::mps(2),
             Length::millimeters(6) / Time::milliseconds(3)
         );
+        assert_eq!(
+            Velocity::kmph(36.),
+            Length::kilometers(1.) / Time::seconds(100.)
+        );
+    }
+    #[test]
+    fn divide_by_time() {
+        assert_eq!(Acceleration::mps2(2.), Velocity::mps(6.) / Time::seconds(3));
+        // Below a second an integer time used to truncate to zero seconds
+        assert_eq!(
+            Acceleration::mps2(2.),
+            Velocity::mps(3.) / Time::milliseconds(1500)
+        );
+        assert_eq!(
+            Acceleration::kmphps(2.5),
+            Velocity::kmph(25.) / Time::seconds(10.)
+        );
     }
 }
@@ -0,0 +1,462 @@
+mod acceleration;
+mod beacon;
+mod brake;
+mod config;
+mod continuity;
+mod display;
+mod notice;
+mod odometry;
+mod pattern;
+mod restriction;
+mod signal;
+mod slow_order;
+mod target;
+mod verify;
+
+use crate::bve::{
+    library_path, AtsModule, Beacon, ConstantSpeed, HandleInitialPosition, HandleModel, Handles,
+    Key, Length, NotchPosition, PanelId, PanelSound, ReverserPosition, SoundManager, VehicleSpec,
+    VehicleState, Velocity,
+};
+use crate::koatc::acceleration::AccelerationEstimator;
+use crate::koatc::beacon::{System, Telegram};
+use crate::koatc::brake::{BrakeController, BrakeInput, Intervention};
+use crate::koatc::config::{Config, KeyBindings, SoundLayout};
+use crate::koatc::continuity::{Continuity, ContinuityChecker};
+use crate::koatc::display::{CabIndication, PanelLayout};
+use crate::koatc::notice::AdvanceNotice;
+use crate::koatc::odometry::Odometer;
+use crate::koatc::pattern::BrakingCurve;
+use crate::koatc::restriction::SpeedRestrictions;
+use crate::koatc::signal::SignalCode;
+use crate::koatc::slow_order::{SlowOrder, SlowOrders};
+use crate::koatc::target::{Envelope, Target, TargetSource, TargetStore};
+use crate::koatc::verify::BrakeMonitor;
+use std::ffi::{c_double, c_int};
+
+// Handle positions last reported by the host
+struct DriverHandles {
+    power: NotchPosition,
+    brake: NotchPosition,
+    reverser: ReverserPosition,
+}
+
+pub struct KoAtc {
+    handles: Option<HandleModel>,
+    driver: DriverHandles,
+    sounds: SoundManager,
+    layout: PanelLayout,
+    sound: SoundLayout,
+    keys: KeyBindings,
+    car_length: Length<c_double>,
+    // Where the ATC believes the train is: BVE's location, or the odometer when enabled
+    location: Length<c_double>,
+    odometer: Option<Odometer>,
+    acceleration: AccelerationEstimator,
+    targets: TargetStore,
+    envelope: Option<Envelope>,
+    notice: AdvanceNotice,
+    restrictions: SpeedRestrictions,
+    slow_orders: SlowOrders,
+    slow_order_limit: Option<Velocity>,
+    signal: SignalCode,
+    brake: BrakeController,
+    monitor: BrakeMonitor,
+    continuity: ContinuityChecker,
+    // System selected at every start of a run, before any beacon switches it
+    initial_system: System,
+    system: System,
+    ground_emergency: bool,
+}
+
+impl KoAtc {
+    // Lowest of the forward pattern and the limits of the zones the train is in: speed
+    // restrictions, slow orders and, on conventional-signal routes, the signal speed code
+    fn allowed_speed(&self) -> Option<Velocity> {
+        let signal = (self.system == System::SignalCode)
+            .then(|| self.signal.limit())
+            .flatten();
+        [
+            self.envelope.map(|e| e.allowed_speed),
+            self.restrictions.limit(self.location),
+            self.slow_order_limit,
+            signal,
+        ]
+        .into_iter()
+        .flatten()
+        .min_by(|a, b| a.as_mps().total_cmp(&b.as_mps()))
+    }
+    // Everything measured from where the train was or from the old clock. Slow orders are
+    // absolute and kept, and their targets go back into the store.
+    fn drop_run_state(&mut self) {
+        self.sounds.stop_all();
+        self.targets.clear();
+        for target in self.slow_orders.targets() {
+            self.targets.insert(target);
+        }
+        self.envelope = None;
+        self.notice.clear();
+        self.restrictions.clear();
+        self.signal.clear();
+        self.brake.reset();
+        self.monitor.reset();
+        self.acceleration.reset();
+        if let Some(odometer) = &mut self.odometer {
+            odometer.reset();
+        }
+    }
+    // The next section's code is braked into only while running on signal codes
+    fn sync_signal_target(&mut self) {
+        match self.signal.target() {
+            Some(target) if self.system == System::SignalCode => self.targets.insert(target),
+            _ => self.targets.remove(TargetSource::SignalCode),
+        }
+    }
+    fn resync(&mut self, continuity: Continuity) {
+        match continuity {
+            Continuity::Normal => {}
+            Continuity::JumpForward | Continuity::JumpBack => self.drop_run_state(),
+            // Locations still hold; only timers started on the old clock are dropped. A brake
+            // failure stays latched.
+            Continuity::TimeRewind | Continuity::TimeSkip => {
+                self.sounds.stop_all();
+                self.monitor.restart_timers();
+                self.acceleration.reset();
+            }
+        }
+    }
+    // ATC brake never weakens the driver's own brake
+    fn merge_handles(&self, intervention: Intervention) -> Handles {
+        let driver = Handles {
+            brake: self.driver.brake,
+            power: self.driver.power,
+            reverser: self.driver.reverser,
+            constant_speed: ConstantSpeed::Continue,
+        };
+        let Some(handles) = self.handles else {
+            return driver;
+        };
+        if intervention == Intervention::None {
+            return driver;
+        }
+        Handles {
+            brake: driver.brake.max(handles.brake(intervention.command())),
+            power: NotchPosition::NEUTRAL,
+            ..driver
+        }
+    }
+}
+
+impl AtsModule for KoAtc {
+    fn load() -> Self {
+        let config = library_path()
+            .map(|path| Config::load(&path.with_extension("ini")))
+            .unwrap_or_default();
+        let curve = BrakingCurve::new(config.deceleration);
+        let mut targets = TargetStore::new(curve, config.stop_window);
+        let mut slow_orders = SlowOrders::new(config.slow_order_warning);
+        for target in config
+            .slow_orders
+            .into_iter()
+            .flat_map(|order| slow_orders.add(order))
+        {
+            targets.insert(target);
+        }
+        KoAtc {
+            handles: None,
+            driver: DriverHandles {
+                power: NotchPosition::NEUTRAL,
+                brake: NotchPosition::NEUTRAL,
+                reverser: ReverserPosition::NEUTRAL,
+            },
+            sounds: SoundManager::new(),
+            layout: config.panel,
+            sound: config.sound,
+            keys: config.keys,
+            car_length: config.car_length,
+            location: Length::meters(0.),
+            odometer: config.odometry.then(|| Odometer::new(config.wheel_error)),
+            acceleration: AccelerationEstimator::new(
+                config.acceleration_window,
+                config.acceleration_filter,
+            ),
+            targets,
+            envelope: None,
+            notice: AdvanceNotice::new(config.notice_horizon),
+            restrictions: SpeedRestrictions::new(),
+            slow_orders,
+            slow_order_limit: None,
+            signal: SignalCode::new(config.speed_codes),
+            brake: BrakeController::new(config.hysteresis, config.emergency_margin),
+            monitor: BrakeMonitor::new(
+                config.applied_pressure,
+                config.apply_time,
+                config.released_pressure,
+                config.release_time,
+            ),
+            continuity: ContinuityChecker::new(config.jump_tolerance, config.max_tick),
+            initial_system: config.system,
+            system: config.system,
+            ground_emergency: false,
+        }
+    }
+    fn fault_lamp(&self) -> Option<PanelId> {
+        Some(self.layout.fault_lamp)
+    }
+    fn set_vehicle_spec(&mut self, spec: &VehicleSpec) {
+        self.handles = Some(spec.handles());
+        let train_length = Length::meters(self.car_length.as_meters() * spec.cars() as c_double);
+        self.restrictions.set_train_length(train_length);
+        self.slow_orders.set_train_length(train_length);
+    }
+    fn initialize(&mut self, _handle: HandleInitialPosition) {
+        self.drop_run_state();
+        self.continuity.reset();
+        self.system = self.initial_system;
+        self.ground_emergency = false;
+        self.sync_signal_target();
+    }
+    fn tick(&mut self, state: &VehicleState, output: &mut PanelSound) -> Handles {
+        let continuity = self
+            .continuity
+            .check(state.location(), state.speed(), state.time());
+        self.resync(continuity);
+        self.location = match &mut self.odometer {
+            Some(odometer) => odometer.update(state.location(), state.speed(), state.time()),
+            None => state.location(),
+        };
+        let acceleration = self.acceleration.update(state.speed(), state.time());
+        self.targets.expire(self.location, state.speed());
+        self.restrictions.expire(self.location);
+        self.signal.update(self.location);
+        let slow_order = self.slow_orders.update(self.location);
+        self.slow_order_limit = slow_order.limit;
+        if slow_order.warn {
+            self.sounds.play(self.sound.slow_order_chime);
+        }
+        let previous = self.brake.intervention();
+        self.envelope = self.targets.envelope(self.location);
+        let notice = self
+            .notice
+            .update(&self.targets, self.location, state.speed());
+        if notice.chime {
+            self.sounds.play(self.sound.notice_chime);
+        }
+        // BC pressure of this frame answers the command of the previous one
+        let check = self.monitor.update(
+            previous != Intervention::None,
+            self.driver.brake > NotchPosition::NEUTRAL,
+            state.bc_pressure(),
+            state.time(),
+        );
+        let intervention = if self.system == System::Off {
+            self.brake.reset();
+            Intervention::None
+        } else {
+            self.brake.update(&BrakeInput {
+                speed: state.speed(),
+                allowed_speed: self.allowed_speed(),
+                overrun: self.targets.overrun(self.location),
+                ground_emergency: self.ground_emergency,
+                brake_failure: check.failure,
+                time: state.time(),
+            })
+        };
+        self.ground_emergency = false;
+        if intervention == Intervention::None && previous != Intervention::None {
+            self.sounds.play(self.sound.atc_bell);
+        }
+        self.sounds.set_loop(
+            self.sound.overspeed_alarm,
+            intervention != Intervention::None,
+        );
+        self.sounds.update(state.time(), output);
+        // The noticed target takes over the digits until the train has slowed down for it
+        let target = notice.target.or(self.envelope.map(|e| e.limiting));
+        let reason = self.brake.last_transition().map(|t| t.reason as c_int);
+        self.layout.write(
+            &CabIndication {
+                permitted_speed: self.allowed_speed(),
+                target_speed: target.map(|t| t.speed),
+                target_distance: target.map(|t| t.location - self.location),
+                atc: self.system != System::Off,
+                brake: intervention != Intervention::None,
+                emergency: intervention == Intervention::Emergency,
+                notice: notice.target.is_some(),
+                // Without the vehicle spec the ATC has no brake notch to apply
+                fault: self.handles.is_none() || check.failure,
+                brake_not_released: check.not_released,
+                slow_order: slow_order.indicated,
+                brake_reason: reason.unwrap_or(0),
+                odometer_error: self.location - state.location(),
+                acceleration,
+            },
+            output,
+        );
+        self.merge_handles(intervention)
+    }
+    fn power(&mut self, power: NotchPosition) {
+        self.driver.power = power;
+    }
+    fn brake(&mut self, brake: NotchPosition) {
+        self.driver.brake = brake;
+    }
+    fn reverser(&mut self, reverser: ReverserPosition) {
+        self.driver.reverser = reverser;
+    }
+    // Cab switch between KO-ATC and the signal speed codes; has no effect while ATC is off
+    fn key_down(&mut self, key: Key) {
+        if self.keys.mode != Some(key) {
+            return;
+        }
+        self.system = match self.system {
+            System::Off => System::Off,
+            System::KoAtc => System::SignalCode,
+            System::SignalCode => System::KoAtc,
+        };
+        self.sync_signal_target();
+    }
+    fn set_signal(&mut self, signal: c_int) {
+        self.signal.set_signal(signal);
+    }
+    fn receive_beacon(&mut self, beacon: &Beacon) {
+        // Any beacon linked to a section tells the aspect of the next signal
+        let distance = beacon.distance().as_::<c_double>();
+        if distance > Length::meters(0.) {
+            self.signal.preload(beacon.signal, self.location + distance);
+            self.sync_signal_target();
+        }
+        // Routes carry beacons for other systems too
+        let Ok(telegram) = Telegram::try_from(beacon) else {
+            return;
+        };
+        match telegram {
+            Telegram::StopTarget {
+                id,
+                distance: Some(distance),
+            } => self.targets.insert(Target {
+                location: self.location + distance,
+                speed: Velocity::mps(0.),
+                source: TargetSource::StopTarget(id),
+            }),
+            Telegram::StopTarget { id, distance: None } => {
+                self.targets.remove(TargetSource::StopTarget(id));
+            }
+            Telegram::StationStop { distance } => self.targets.insert(Target {
+                location: self.location + distance,
+                speed: Velocity::mps(0.),
+                source: TargetSource::StationStop,
+            }),
+            Telegram::SpeedLimitStart {
+                limit,
+                distance,
+                length,
+            } => {
+                let start = self.location + distance;
+                let target = self.restrictions.start(start, limit, length);
+                self.targets.insert(target);
+            }
+            Telegram::SpeedLimitEnd => self.restrictions.end(self.location),
+            Telegram::SystemSwitch(system) => {
+                self.system = system;
+                self.sync_signal_target();
+            }
+            Telegram::Emergency => self.ground_emergency = true,
+            Telegram::SlowOrder {
+                limit,
+                distance,
+                length,
+            } => {
+                let order = SlowOrder {
+                    start: self.location + distance,
+                    end: self.location + distance + length,
+                    limit,
+                };
+                if let Some(target) = self.slow_orders.add(order) {
+                    self.targets.insert(target);
+                }
+            }
+            Telegram::PositionCorrect => {
+                if let Some(odometer) = &mut self.odometer {
+                    odometer.correct();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bve::{BeaconType, Pressure, Time};
+
+    #[test]
+    fn preload_signal() {
+        let mut atc = KoAtc::load();
+        atc.system = System::SignalCode;
+        atc.location = Length::meters(1000.);
+        atc.set_signal(2);
+        // As SetBeaconData passes it: the distance to the next signal in metres
+        atc.receive_beacon(&Beacon {
+            beacon_type: BeaconType(0),
+            signal: 1,
+            distance: 600.,
+            optional: 0,
+        });
+        let boundary = Target {
+            location: Length::meters(1600.),
+            speed: atc.signal.code(1),
+            source: TargetSource::SignalCode,
+        };
+        assert_eq!(
+            Some(boundary),
+            atc.targets.envelope(atc.location).map(|e| e.limiting)
+        );
+        atc.signal.update(Length::meters(1599.));
+        assert_eq!(Some(atc.signal.code(2)), atc.signal.limit());
+        atc.signal.update(Length::meters(1600.));
+        assert_eq!(Some(atc.signal.code(1)), atc.signal.limit());
+    }
+    #[test]
+    fn restriction_approach() {
+        let mut atc = KoAtc::load();
+        atc.location = Length::meters(1000.);
+        // 45 km/h from 500 m ahead for 100 m
+        atc.receive_beacon(&Beacon {
+            beacon_type: BeaconType(31),
+            signal: 0,
+            distance: 0.,
+            optional: 45_050_010,
+        });
+        let limit = Velocity::kilometer_per_hour(45);
+        assert_eq!(None, atc.restrictions.limit(atc.location));
+        let envelope = atc.targets.envelope(atc.location).unwrap();
+        assert_eq!(Length::meters(1500.), envelope.limiting.location);
+        assert_eq!(limit, envelope.limiting.speed);
+        assert!(envelope.allowed_speed > Velocity::kilometer_per_hour(100));
+        assert_eq!(Some(limit), atc.restrictions.limit(Length::meters(1500.)));
+    }
+    #[test]
+    fn failure_survives_time_skip() {
+        let mut atc = KoAtc::load();
+        let bc = Pressure::kpa(0);
+        atc.monitor.update(true, false, bc, Time::seconds(0));
+        assert!(
+            atc.monitor
+                .update(true, false, bc, Time::seconds(10))
+                .failure
+        );
+        atc.resync(Continuity::TimeSkip);
+        assert!(
+            atc.monitor
+                .update(false, false, bc, Time::seconds(20))
+                .failure
+        );
+        atc.resync(Continuity::TimeRewind);
+        assert!(
+            atc.monitor
+                .update(false, false, bc, Time::seconds(5))
+                .failure
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use crate::bve::{Acceleration, Time, Velocity};
+use std::collections::VecDeque;
+use std::ffi::{c_double, c_int};
+
+// Acceleration from the speed BVE reports: the speed change over a sliding window, smoothed
+// by a first-order low-pass filter.
+pub struct AccelerationEstimator {
+    window: Time<c_int>,
+    // Time constant; zero passes the windowed estimate through
+    filter: Time<c_double>,
+    samples: VecDeque<(Time<c_int>, Velocity)>,
+    estimate: Option<Acceleration>,
+}
+
+impl AccelerationEstimator {
+    pub fn new(window: Time<c_int>, filter: Time<c_double>) -> Self {
+        Self {
+            window,
+            filter,
+            samples: VecDeque::new(),
+            estimate: None,
+        }
+    }
+    // Forget the history, e.g. when the clock or the location jumped
+    pub fn reset(&mut self) {
+        self.samples.clear();
+        self.estimate = None;
+    }
+    // Zero until there are two frames to compare
+    pub fn update(&mut self, speed: Velocity, time: Time<c_int>) -> Acceleration {
+        match self.samples.back() {
+            // A zero-length frame carries no new information
+            Some(&(last, _)) if time == last => return self.acceleration(),
+            Some(&(last, _)) if time < last => self.reset(),
+            _ => {}
+        }
+        let elapsed = self
+            .samples
+            .back()
+            .map(|&(last, _)| (time - last).as_::<c_double>());
+        self.samples.push_back((time, speed));
+        // Keep the newest sample that is at least a window old as the reference
+        while self.samples.len() > 2 && time - self.samples[1].0 >= self.window {
+            self.samples.pop_front();
+        }
+        let (Some(elapsed), Some(&(since, reference))) = (elapsed, self.samples.front()) else {
+            return self.acceleration();
+        };
+        let raw = (speed - reference) / (time - since).as_::<c_double>();
+        self.estimate = Some(match self.estimate {
+            Some(estimate) => {
+                let elapsed = elapsed.as_milliseconds();
+                let gain = elapsed / (self.filter.as_milliseconds() + elapsed);
+                estimate + (raw - estimate) * gain
+            }
+            None => raw,
+        });
+        self.acceleration()
+    }
+    fn acceleration(&self) -> Acceleration {
+        self.estimate.unwrap_or(Acceleration::mps2(0.))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimator() -> AccelerationEstimator {
+        AccelerationEstimator::new(Time::milliseconds(500), Time::milliseconds(0.))
+    }
+
+    #[test]
+    fn irregular_ticks() {
+        let mut estimator = estimator();
+        assert_eq!(
+            Acceleration::mps2(0.),
+            estimator.update(Velocity::mps(10.), Time::milliseconds(0))
+        );
+        // -1 m/s² throughout, whatever the frame length
+        for (time, speed) in [(16, 9.984), (50, 9.95), (300, 9.7), (1000, 9.)] {
+            let acceleration = estimator.update(Velocity::mps(speed), Time::milliseconds(time));
+            assert!((acceleration.as_mps2() + 1.).abs() < 1e-9);
+        }
+    }
+    #[test]
+    fn window() {
+        let mut estimator = estimator();
+        for time in (0..=1000).step_by(100) {
+            estimator.update(Velocity::mps(10.), Time::milliseconds(time));
+        }
+        // A step is spread over the window
+        let acceleration = estimator.update(Velocity::mps(10.5), Time::milliseconds(1100));
+        assert!((acceleration.as_mps2() - 1.).abs() < 1e-9);
+    }
+    #[test]
+    fn filter() {
+        let mut estimator =
+            AccelerationEstimator::new(Time::milliseconds(100), Time::milliseconds(100.));
+        estimator.update(Velocity::mps(0.), Time::milliseconds(0));
+        estimator.update(Velocity::mps(0.), Time::milliseconds(100));
+        let acceleration = estimator.update(Velocity::mps(0.1), Time::milliseconds(200));
+        assert!((acceleration.as_mps2() - 0.5).abs() < 1e-9);
+    }
+    #[test]
+    fn discontinuities() {
+        let mut estimator = estimator();
+        estimator.update(Velocity::mps(10.), Time::milliseconds(0));
+        let acceleration = estimator.update(Velocity::mps(9.9), Time::milliseconds(100));
+        // Same clock again
+        assert_eq!(
+            acceleration,
+            estimator.update(Velocity::mps(5.), Time::milliseconds(100))
+        );
+        // Rewound clock
+        assert_eq!(
+            Acceleration::mps2(0.),
+            estimator.update(Velocity::mps(0.), Time::milliseconds(50))
+        );
+    }
+}
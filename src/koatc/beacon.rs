@@ -0,0 +1,245 @@
+use crate::bve::{Beacon, Length, Velocity};
+use std::ffi::{c_double, c_int};
+
+// Beacon types understood by KO-ATC and the packing of their `optional` value.
+// Packed fields are decimal so that route authors can write them by hand.
+//
+// | type | telegram        | optional                                       |
+// |------|-----------------|------------------------------------------------|
+// | 30   | StopTarget      | target ID * 100000 + distance [m]; 0 m clears  |
+// | 31   | SpeedLimitStart | speed [km/h] * 1000000 + distance to start     |
+// |      |                 | [10 m] * 1000 + length [10 m]                  |
+// | 32   | SpeedLimitEnd   | unused                                         |
+// | 33   | SystemSwitch    | 0: ATC off, 1: KO-ATC, 2: signal speed codes   |
+// | 34   | StationStop     | distance to the stop position [m]              |
+// | 35   | Emergency       | unused                                         |
+// | 36   | SlowOrder       | speed [km/h] * 1000000 + distance to start     |
+// |      |                 | [10 m] * 1000 + length [10 m]                  |
+// | 37   | PositionCorrect | unused                                         |
+const STOP_TARGET: u32 = 30;
+const SPEED_LIMIT_START: u32 = 31;
+const SPEED_LIMIT_END: u32 = 32;
+const SYSTEM_SWITCH: u32 = 33;
+const STATION_STOP: u32 = 34;
+const EMERGENCY: u32 = 35;
+const SLOW_ORDER: u32 = 36;
+const POSITION_CORRECT: u32 = 37;
+
+const TARGET_ID_UNIT: c_int = 100_000;
+// Packing of the zone telegrams, SpeedLimitStart and SlowOrder
+const ZONE_SPEED_UNIT: c_int = 1_000_000;
+const ZONE_DISTANCE_UNIT: c_int = 1_000;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum System {
+    Off,
+    KoAtc,
+    // Speed codes from the section signals, for routes without KO-ATC telegrams
+    SignalCode,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Telegram {
+    StopTarget {
+        id: c_int,
+        // None clears the target
+        distance: Option<Length<c_double>>,
+    },
+    SpeedLimitStart {
+        limit: Velocity,
+        distance: Length<c_double>,
+        length: Length<c_double>,
+    },
+    SpeedLimitEnd,
+    SystemSwitch(System),
+    StationStop {
+        distance: Length<c_double>,
+    },
+    Emergency,
+    SlowOrder {
+        limit: Velocity,
+        distance: Length<c_double>,
+        length: Length<c_double>,
+    },
+    // Ground coil at a surveyed location, for the odometer
+    PositionCorrect,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum DecodeError {
+    UnknownType(u32),
+    // The type is known but `optional` does not follow its packing
+    InvalidOptional { beacon_type: u32, optional: c_int },
+}
+
+impl TryFrom<&Beacon> for Telegram {
+    type Error = DecodeError;
+
+    fn try_from(beacon: &Beacon) -> Result<Self, Self::Error> {
+        let beacon_type = beacon.beacon_type.0;
+        let optional = beacon.optional;
+        let invalid = DecodeError::InvalidOptional {
+            beacon_type,
+            optional,
+        };
+        if optional < 0 {
+            return Err(invalid);
+        }
+        let meters = |value: c_int| Length::meters(value as c_double);
+        Ok(match beacon_type {
+            STOP_TARGET => Telegram::StopTarget {
+                id: optional / TARGET_ID_UNIT,
+                distance: Some(optional % TARGET_ID_UNIT)
+                    .filter(|&distance| distance > 0)
+                    .map(meters),
+            },
+            SPEED_LIMIT_START if optional % ZONE_DISTANCE_UNIT == 0 => return Err(invalid),
+            SPEED_LIMIT_START => Telegram::SpeedLimitStart {
+                limit: Velocity::kilometer_per_hour(optional / ZONE_SPEED_UNIT),
+                distance: meters(optional % ZONE_SPEED_UNIT / ZONE_DISTANCE_UNIT * 10),
+                length: meters(optional % ZONE_DISTANCE_UNIT * 10),
+            },
+            SPEED_LIMIT_END => Telegram::SpeedLimitEnd,
+            SYSTEM_SWITCH => match optional {
+                0 => Telegram::SystemSwitch(System::Off),
+                1 => Telegram::SystemSwitch(System::KoAtc),
+                2 => Telegram::SystemSwitch(System::SignalCode),
+                _ => return Err(invalid),
+            },
+            STATION_STOP => Telegram::StationStop {
+                distance: meters(optional),
+            },
+            EMERGENCY => Telegram::Emergency,
+            SLOW_ORDER if optional % ZONE_DISTANCE_UNIT == 0 => return Err(invalid),
+            SLOW_ORDER => Telegram::SlowOrder {
+                limit: Velocity::kilometer_per_hour(optional / ZONE_SPEED_UNIT),
+                distance: meters(optional % ZONE_SPEED_UNIT / ZONE_DISTANCE_UNIT * 10),
+                length: meters(optional % ZONE_DISTANCE_UNIT * 10),
+            },
+            POSITION_CORRECT => Telegram::PositionCorrect,
+            _ => return Err(DecodeError::UnknownType(beacon_type)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bve::BeaconType;
+
+    fn decode(beacon_type: u32, optional: c_int) -> Result<Telegram, DecodeError> {
+        Telegram::try_from(&Beacon {
+            beacon_type: BeaconType(beacon_type),
+            signal: 0,
+            distance: 0.,
+            optional,
+        })
+    }
+
+    #[test]
+    fn stop_target() {
+        assert_eq!(
+            Ok(Telegram::StopTarget {
+                id: 0,
+                distance: Some(Length::meters(350.))
+            }),
+            decode(STOP_TARGET, 350)
+        );
+        assert_eq!(
+            Ok(Telegram::StopTarget {
+                id: 2,
+                distance: Some(Length::meters(1200.))
+            }),
+            decode(STOP_TARGET, 201200)
+        );
+        assert_eq!(
+            Ok(Telegram::StopTarget {
+                id: 2,
+                distance: None
+            }),
+            decode(STOP_TARGET, 200000)
+        );
+    }
+    #[test]
+    fn speed_limit() {
+        assert_eq!(
+            Ok(Telegram::SpeedLimitStart {
+                limit: Velocity::kilometer_per_hour(45),
+                distance: Length::meters(600.),
+                length: Length::meters(320.)
+            }),
+            decode(SPEED_LIMIT_START, 45_060_032)
+        );
+        assert_eq!(
+            Ok(Telegram::SpeedLimitStart {
+                limit: Velocity::kilometer_per_hour(45),
+                distance: Length::meters(0.),
+                length: Length::meters(320.)
+            }),
+            decode(SPEED_LIMIT_START, 45_000_032)
+        );
+        assert_eq!(
+            Err(DecodeError::InvalidOptional {
+                beacon_type: SPEED_LIMIT_START,
+                optional: 45_060_000
+            }),
+            decode(SPEED_LIMIT_START, 45_060_000)
+        );
+        assert_eq!(Ok(Telegram::SpeedLimitEnd), decode(SPEED_LIMIT_END, 0));
+    }
+    #[test]
+    fn others() {
+        assert_eq!(
+            Ok(Telegram::SystemSwitch(System::KoAtc)),
+            decode(SYSTEM_SWITCH, 1)
+        );
+        assert_eq!(
+            Ok(Telegram::SystemSwitch(System::SignalCode)),
+            decode(SYSTEM_SWITCH, 2)
+        );
+        assert_eq!(
+            Err(DecodeError::InvalidOptional {
+                beacon_type: SYSTEM_SWITCH,
+                optional: 7
+            }),
+            decode(SYSTEM_SWITCH, 7)
+        );
+        assert_eq!(
+            Ok(Telegram::StationStop {
+                distance: Length::meters(500.)
+            }),
+            decode(STATION_STOP, 500)
+        );
+        assert_eq!(Ok(Telegram::Emergency), decode(EMERGENCY, 0));
+        assert_eq!(Ok(Telegram::PositionCorrect), decode(POSITION_CORRECT, 0));
+    }
+    #[test]
+    fn slow_order() {
+        assert_eq!(
+            Ok(Telegram::SlowOrder {
+                limit: Velocity::kilometer_per_hour(25),
+                distance: Length::meters(600.),
+                length: Length::meters(1200.)
+            }),
+            decode(SLOW_ORDER, 25_060_120)
+        );
+        assert_eq!(
+            Err(DecodeError::InvalidOptional {
+                beacon_type: SLOW_ORDER,
+                optional: 25_060_000
+            }),
+            decode(SLOW_ORDER, 25_060_000)
+        );
+    }
+    #[test]
+    fn rejected() {
+        assert_eq!(Err(DecodeError::UnknownType(0)), decode(0, 0));
+        assert_eq!(
+            Err(DecodeError::InvalidOptional {
+                beacon_type: STOP_TARGET,
+                optional: -1
+            }),
+            decode(STOP_TARGET, -1)
+        );
+    }
+}
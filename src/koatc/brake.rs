@@ -0,0 +1,297 @@
+use crate::bve::{BrakeCommand, Time, Velocity};
+use std::collections::VecDeque;
+use std::ffi::c_int;
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum Intervention {
+    None,
+    Service,
+    Emergency,
+}
+impl Intervention {
+    pub fn command(&self) -> BrakeCommand {
+        match self {
+            Intervention::None => BrakeCommand::Release,
+            Intervention::Service => BrakeCommand::FullService,
+            Intervention::Emergency => BrakeCommand::Emergency,
+        }
+    }
+}
+
+// Why the intervention level changed; the discriminant is shown on the panel
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum BrakeReason {
+    // Train speed went above the pattern
+    Overspeed = 1,
+    // Train speed went above the pattern by more than the emergency margin
+    LargeOverspeed = 2,
+    // Train passed the stop target while moving
+    TargetOverrun = 3,
+    // Train speed fell below the pattern minus the hysteresis
+    BelowPattern = 4,
+    // Emergency brake held until the train stopped
+    Stopped = 5,
+    // Emergency telegram from the ground
+    GroundEmergency = 6,
+    // BC pressure did not follow the ATC brake command
+    BrakeFailure = 7,
+}
+
+// Only the reason is shown on the panel; the rest is there for whoever inspects the history
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug)]
+pub struct Transition {
+    pub from: Intervention,
+    pub to: Intervention,
+    pub reason: BrakeReason,
+    pub time: Time<c_int>,
+}
+
+// Transitions kept for inspection; older ones are dropped
+const TRANSITION_HISTORY: usize = 16;
+
+pub struct BrakeInput {
+    pub speed: Velocity,
+    // None while no pattern is in force
+    pub allowed_speed: Option<Velocity>,
+    pub overrun: bool,
+    pub ground_emergency: bool,
+    // Held until reset, even at standstill
+    pub brake_failure: bool,
+    pub time: Time<c_int>,
+}
+
+// Service brake above the pattern, released below pattern minus hysteresis.
+// Emergency brake on a large overspeed, a stop-target overrun or a ground emergency telegram,
+// held until the train stops; on a brake failure, held for as long as the failure is.
+pub struct BrakeController {
+    hysteresis: Velocity,
+    emergency_margin: Velocity,
+    intervention: Intervention,
+    transitions: VecDeque<Transition>,
+}
+
+impl BrakeController {
+    pub fn new(hysteresis: Velocity, emergency_margin: Velocity) -> Self {
+        Self {
+            hysteresis,
+            emergency_margin,
+            intervention: Intervention::None,
+            transitions: VecDeque::with_capacity(TRANSITION_HISTORY),
+        }
+    }
+    pub fn intervention(&self) -> Intervention {
+        self.intervention
+    }
+    pub fn last_transition(&self) -> Option<Transition> {
+        self.transitions.back().copied()
+    }
+    // The most recent transitions, oldest first
+    #[allow(dead_code)]
+    pub fn transitions(&self) -> impl Iterator<Item = &Transition> {
+        self.transitions.iter()
+    }
+    pub fn reset(&mut self) {
+        self.intervention = Intervention::None;
+        self.transitions.clear();
+    }
+    pub fn update(&mut self, input: &BrakeInput) -> Intervention {
+        let moving = input.speed > Velocity::mps(0.);
+        let next = match (self.intervention, input.allowed_speed) {
+            _ if input.brake_failure => Some((Intervention::Emergency, BrakeReason::BrakeFailure)),
+            _ if input.ground_emergency => {
+                Some((Intervention::Emergency, BrakeReason::GroundEmergency))
+            }
+            _ if input.overrun && moving => {
+                Some((Intervention::Emergency, BrakeReason::TargetOverrun))
+            }
+            (Intervention::Emergency, _) if moving => None,
+            (Intervention::Emergency, _) => Some((Intervention::None, BrakeReason::Stopped)),
+            (_, Some(allowed)) if input.speed > allowed + self.emergency_margin => {
+                Some((Intervention::Emergency, BrakeReason::LargeOverspeed))
+            }
+            (Intervention::None, Some(allowed)) if input.speed > allowed => {
+                Some((Intervention::Service, BrakeReason::Overspeed))
+            }
+            (Intervention::Service, None) => Some((Intervention::None, BrakeReason::BelowPattern)),
+            (Intervention::Service, Some(allowed)) if input.speed < allowed - self.hysteresis => {
+                Some((Intervention::None, BrakeReason::BelowPattern))
+            }
+            _ => None,
+        };
+        if let Some((to, reason)) = next {
+            self.transit(to, reason, input.time);
+        }
+        self.intervention
+    }
+    fn transit(&mut self, to: Intervention, reason: BrakeReason, time: Time<c_int>) {
+        if to == self.intervention {
+            return;
+        }
+        if self.transitions.len() == TRANSITION_HISTORY {
+            self.transitions.pop_front();
+        }
+        self.transitions.push_back(Transition {
+            from: self.intervention,
+            to,
+            reason,
+            time,
+        });
+        self.intervention = to;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(speed: f64, allowed: Option<f64>, overrun: bool) -> BrakeInput {
+        BrakeInput {
+            speed: Velocity::kilometer_per_hour(speed),
+            allowed_speed: allowed.map(Velocity::kilometer_per_hour),
+            overrun,
+            ground_emergency: false,
+            brake_failure: false,
+            time: Time::milliseconds(0),
+        }
+    }
+    fn controller() -> BrakeController {
+        BrakeController::new(
+            Velocity::kilometer_per_hour(5.),
+            Velocity::kilometer_per_hour(10.),
+        )
+    }
+
+    #[test]
+    fn service() {
+        let mut brake = controller();
+        assert_eq!(
+            Intervention::None,
+            brake.update(&input(60., Some(65.), false))
+        );
+        assert_eq!(
+            Intervention::Service,
+            brake.update(&input(66., Some(65.), false))
+        );
+        assert_eq!(
+            Some(BrakeReason::Overspeed),
+            brake.last_transition().map(|t| t.reason)
+        );
+        assert_eq!(
+            Intervention::Service,
+            brake.update(&input(62., Some(65.), false))
+        );
+        assert_eq!(
+            Intervention::None,
+            brake.update(&input(59., Some(65.), false))
+        );
+        assert_eq!(
+            Some(BrakeReason::BelowPattern),
+            brake.last_transition().map(|t| t.reason)
+        );
+    }
+    #[test]
+    fn emergency() {
+        let mut brake = controller();
+        assert_eq!(
+            Intervention::Emergency,
+            brake.update(&input(76., Some(65.), false))
+        );
+        assert_eq!(
+            Some(BrakeReason::LargeOverspeed),
+            brake.last_transition().map(|t| t.reason)
+        );
+        assert_eq!(
+            Intervention::Emergency,
+            brake.update(&input(30., Some(65.), false))
+        );
+        assert_eq!(
+            Intervention::None,
+            brake.update(&input(0., Some(65.), false))
+        );
+        assert_eq!(
+            Some(BrakeReason::Stopped),
+            brake.last_transition().map(|t| t.reason)
+        );
+    }
+    #[test]
+    fn overrun() {
+        let mut brake = controller();
+        assert_eq!(
+            Intervention::Emergency,
+            brake.update(&input(3., Some(0.), true))
+        );
+        assert_eq!(
+            Some(BrakeReason::TargetOverrun),
+            brake.last_transition().map(|t| t.reason)
+        );
+        assert_eq!(Intervention::None, brake.update(&input(0., Some(0.), true)));
+    }
+    #[test]
+    fn ground_emergency() {
+        let mut brake = controller();
+        let emergency = BrakeInput {
+            ground_emergency: true,
+            ..input(40., None, false)
+        };
+        assert_eq!(Intervention::Emergency, brake.update(&emergency));
+        assert_eq!(
+            Some(BrakeReason::GroundEmergency),
+            brake.last_transition().map(|t| t.reason)
+        );
+        assert_eq!(
+            Intervention::Emergency,
+            brake.update(&input(40., None, false))
+        );
+        assert_eq!(Intervention::None, brake.update(&input(0., None, false)));
+    }
+    #[test]
+    fn brake_failure() {
+        let mut brake = controller();
+        let failure = BrakeInput {
+            brake_failure: true,
+            ..input(40., Some(35.), false)
+        };
+        assert_eq!(Intervention::Emergency, brake.update(&failure));
+        assert_eq!(
+            Some(BrakeReason::BrakeFailure),
+            brake.last_transition().map(|t| t.reason)
+        );
+        let stopped = BrakeInput {
+            brake_failure: true,
+            ..input(0., Some(35.), false)
+        };
+        assert_eq!(Intervention::Emergency, brake.update(&stopped));
+    }
+    #[test]
+    fn no_pattern() {
+        let mut brake = controller();
+        assert_eq!(Intervention::None, brake.update(&input(120., None, false)));
+        assert_eq!(
+            Intervention::Service,
+            brake.update(&input(20., Some(15.), false))
+        );
+        assert_eq!(Intervention::None, brake.update(&input(20., None, false)));
+    }
+    #[test]
+    fn history() {
+        let mut brake = controller();
+        for time in 0..=TRANSITION_HISTORY as c_int {
+            let speed = if time % 2 == 0 { 66. } else { 59. };
+            brake.update(&BrakeInput {
+                time: Time::milliseconds(time),
+                ..input(speed, Some(65.), false)
+            });
+        }
+        assert_eq!(TRANSITION_HISTORY, brake.transitions().count());
+        // The transition at 0 ms was dropped
+        let first = brake.transitions().next().unwrap();
+        assert_eq!(Time::milliseconds(1), first.time);
+        assert_eq!(
+            (Intervention::Service, Intervention::None),
+            (first.from, first.to)
+        );
+        brake.reset();
+        assert_eq!(0, brake.transitions().count());
+    }
+}
@@ -0,0 +1,556 @@
+use crate::bve::{
+    Acceleration, Key, Length, PanelId, ParseQuantityError, Pressure, SoundId, Time, Velocity,
+};
+use crate::koatc::beacon::System;
+use crate::koatc::display::PanelLayout;
+use crate::koatc::slow_order::SlowOrder;
+use std::ffi::{c_double, c_int};
+use std::fmt::{Display, Formatter};
+use std::io::ErrorKind;
+use std::path::Path;
+use std::str::FromStr;
+
+// Settings read from an INI file next to the plugin.
+// Quantities take a unit; a bare number is read in the unit shown below. Lists are comma
+// separated.
+//
+// [atc]
+// system = koatc                   ; initial system: off, koatc, signal
+// deceleration = 2.5km/h/s         ; for every braking curve
+// stop_window = 10m
+// hysteresis = 5km/h
+// emergency_margin = 10km/h
+// notice_horizon = 5s
+// speed_codes = 0, 25km/h, 45km/h  ; per signal index
+// [train]
+// car_length = 20m
+// [slow_order]
+// warning_distance = 500m
+// order = 1.2km, 1500m, 25km/h     ; start, end, limit; may be repeated
+// [brake_check]
+// applied_pressure = 150kPa        ; BC pressure expected after an ATC brake command
+// apply_time = 3s                  ; ... within this time, or the brake has failed
+// released_pressure = 50kPa        ; BC pressure expected after the ATC releases
+// release_time = 5s                ; ... within this time, unless the driver is braking
+// [continuity]
+// jump_tolerance = 5m              ; movement beyond the speed allows is a location jump
+// max_tick = 2s                    ; a longer frame is a time skip
+// [odometry]
+// enabled = false                  ; measure the location instead of taking BVE's
+// wheel_error = 1.5%               ; how far the measured distance reads long
+// [acceleration]
+// window = 500ms                   ; speed change is taken over at least this long
+// filter = 200ms                   ; low-pass time constant; 0 turns it off
+// [panel]
+// atc_lamp = 1                     ; one key per PanelLayout field
+// target_speed = 20, 21, 22
+// [sound]
+// atc_bell = 2                     ; one key per SoundLayout field
+// [key]
+// mode = D                         ; switches between KO-ATC and signal speed codes
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub system: System,
+    pub deceleration: Acceleration,
+    pub stop_window: Length<c_double>,
+    pub hysteresis: Velocity,
+    pub emergency_margin: Velocity,
+    pub notice_horizon: Time<c_double>,
+    pub speed_codes: Vec<Velocity>,
+    pub car_length: Length<c_double>,
+    pub slow_order_warning: Length<c_double>,
+    pub slow_orders: Vec<SlowOrder>,
+    pub applied_pressure: Pressure,
+    pub apply_time: Time<c_int>,
+    pub released_pressure: Pressure,
+    pub release_time: Time<c_int>,
+    pub jump_tolerance: Length<c_double>,
+    pub max_tick: Time<c_int>,
+    pub odometry: bool,
+    // Relative, e.g. 0.015 for 1.5%
+    pub wheel_error: c_double,
+    pub acceleration_window: Time<c_int>,
+    pub acceleration_filter: Time<c_double>,
+    pub panel: PanelLayout,
+    pub sound: SoundLayout,
+    pub keys: KeyBindings,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SoundLayout {
+    pub atc_bell: SoundId,
+    pub overspeed_alarm: SoundId,
+    pub slow_order_chime: SoundId,
+    pub notice_chime: SoundId,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct KeyBindings {
+    pub mode: Option<Key>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum ConfigErrorKind {
+    // Neither a section header nor `key = value`
+    Syntax,
+    UnknownSection(String),
+    UnknownKey {
+        section: String,
+        key: String,
+    },
+    InvalidValue {
+        key: String,
+        value: String,
+    },
+    InvalidQuantity {
+        key: String,
+        value: String,
+        error: ParseQuantityError,
+    },
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ConfigError {
+    // 1-based
+    pub line: usize,
+    pub kind: ConfigErrorKind,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            system: System::KoAtc,
+            deceleration: Acceleration::kmphps(2.5),
+            stop_window: Length::meters(10.),
+            hysteresis: Velocity::kilometer_per_hour(5.),
+            emergency_margin: Velocity::kilometer_per_hour(10.),
+            notice_horizon: Time::seconds(5.),
+            speed_codes: [0., 25., 45., 65., 75., 90., 110.]
+                .map(Velocity::kilometer_per_hour)
+                .to_vec(),
+            car_length: Length::meters(20.),
+            slow_order_warning: Length::meters(500.),
+            slow_orders: Vec::new(),
+            applied_pressure: Pressure::kpa(150),
+            apply_time: Time::seconds(3),
+            released_pressure: Pressure::kpa(50),
+            release_time: Time::seconds(5),
+            jump_tolerance: Length::meters(5.),
+            max_tick: Time::seconds(2),
+            odometry: false,
+            wheel_error: 0.,
+            acceleration_window: Time::milliseconds(500),
+            acceleration_filter: Time::milliseconds(200.),
+            panel: PanelLayout::default(),
+            sound: SoundLayout::default(),
+            keys: KeyBindings { mode: None },
+        }
+    }
+}
+
+impl Default for SoundLayout {
+    fn default() -> Self {
+        Self {
+            atc_bell: SoundId(2),
+            overspeed_alarm: SoundId(3),
+            slow_order_chime: SoundId(4),
+            notice_chime: SoundId(5),
+        }
+    }
+}
+
+impl Config {
+    // A missing file gives the defaults; entries in error keep their default and are
+    // reported, the rest of the file still applies
+    pub fn load(path: &Path) -> Self {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == ErrorKind::NotFound => {
+                eprintln!("bve-koatc: no config at {}, using defaults", path.display());
+                return Self::default();
+            }
+            Err(error) => {
+                eprintln!("bve-koatc: cannot read {}: {error}", path.display());
+                return Self::default();
+            }
+        };
+        let (config, errors) = Self::parse(&text);
+        for error in errors {
+            eprintln!("bve-koatc: {}: {error}", path.display());
+        }
+        config
+    }
+    pub fn parse(text: &str) -> (Self, Vec<ConfigError>) {
+        let mut config = Self::default();
+        let mut errors = Vec::new();
+        let mut section = String::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split([';', '#']).next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let result = if let Some(name) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                section = name.trim().to_ascii_lowercase();
+                match section.as_str() {
+                    "atc" | "train" | "slow_order" | "brake_check" | "continuity" | "odometry"
+                    | "acceleration" | "panel" | "sound" | "key" => Ok(()),
+                    _ => Err(ConfigErrorKind::UnknownSection(section.clone())),
+                }
+            } else if let Some((key, value)) = line.split_once('=') {
+                config.set(&section, &key.trim().to_ascii_lowercase(), value.trim())
+            } else {
+                Err(ConfigErrorKind::Syntax)
+            };
+            if let Err(kind) = result {
+                errors.push(ConfigError {
+                    line: index + 1,
+                    kind,
+                });
+            }
+        }
+        (config, errors)
+    }
+    fn set(&mut self, section: &str, key: &str, value: &str) -> Result<(), ConfigErrorKind> {
+        let invalid = || ConfigErrorKind::InvalidValue {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        };
+        let speed = |value: &str| {
+            quantity(key, value, "km/h").and_then(|v| check(v >= Velocity::mps(0.), v, invalid()))
+        };
+        let distance = |value: &str| {
+            quantity(key, value, "m").and_then(|v| check(v >= Length::meters(0.), v, invalid()))
+        };
+        let panel = |value: &str| value.parse::<u8>().map(PanelId).map_err(|_| invalid());
+        let sound = |value: &str| value.parse::<u8>().map(SoundId).map_err(|_| invalid());
+        match (section, key) {
+            ("atc", "system") => {
+                self.system = match value.to_ascii_lowercase().as_str() {
+                    "off" => System::Off,
+                    "koatc" => System::KoAtc,
+                    "signal" => System::SignalCode,
+                    _ => return Err(invalid()),
+                }
+            }
+            ("atc", "deceleration") => {
+                let deceleration = quantity(key, value, "km/h/s")?;
+                self.deceleration = check(
+                    deceleration > Acceleration::mps2(0.),
+                    deceleration,
+                    invalid(),
+                )?;
+            }
+            ("atc", "stop_window") => self.stop_window = distance(value)?,
+            ("atc", "hysteresis") => self.hysteresis = speed(value)?,
+            ("atc", "emergency_margin") => self.emergency_margin = speed(value)?,
+            ("atc", "notice_horizon") => {
+                let horizon = quantity(key, value, "s")?;
+                self.notice_horizon = check(horizon >= Time::seconds(0.), horizon, invalid())?;
+            }
+            ("atc", "speed_codes") => {
+                self.speed_codes = value.split(',').map(speed).collect::<Result<_, _>>()?
+            }
+            ("train", "car_length") => {
+                let car_length = distance(value)?;
+                self.car_length = check(car_length > Length::meters(0.), car_length, invalid())?;
+            }
+            ("slow_order", "warning_distance") => self.slow_order_warning = distance(value)?,
+            ("slow_order", "order") => match value.split(',').collect::<Vec<_>>()[..] {
+                [start, end, limit] => {
+                    let order = SlowOrder {
+                        start: distance(start)?,
+                        end: distance(end)?,
+                        limit: speed(limit)?,
+                    };
+                    self.slow_orders
+                        .push(check(order.start < order.end, order, invalid())?);
+                }
+                _ => return Err(invalid()),
+            },
+            ("brake_check", "applied_pressure" | "released_pressure") => {
+                let pressure = quantity(key, value, "kPa")?;
+                let pressure = check(pressure > Pressure::kpa(0), pressure, invalid())?;
+                if key == "applied_pressure" {
+                    self.applied_pressure = pressure;
+                } else {
+                    self.released_pressure = pressure;
+                }
+            }
+            ("brake_check", "apply_time" | "release_time") => {
+                let time = quantity(key, value, "s")?;
+                let time = check(time > Time::seconds(0), time, invalid())?;
+                if key == "apply_time" {
+                    self.apply_time = time;
+                } else {
+                    self.release_time = time;
+                }
+            }
+            ("continuity", "jump_tolerance") => self.jump_tolerance = distance(value)?,
+            ("continuity", "max_tick") => {
+                let max_tick = quantity(key, value, "s")?;
+                self.max_tick = check(max_tick > Time::seconds(0), max_tick, invalid())?;
+            }
+            ("odometry", "enabled") => {
+                self.odometry = match value.to_ascii_lowercase().as_str() {
+                    "true" | "on" | "1" => true,
+                    "false" | "off" | "0" => false,
+                    _ => return Err(invalid()),
+                }
+            }
+            // In percent, with or without the % sign
+            ("odometry", "wheel_error") => {
+                let percent = value.strip_suffix('%').unwrap_or(value).trim();
+                let error = percent.parse::<c_double>().map_err(|_| invalid())? / 100.;
+                self.wheel_error = check(error.abs() < 1., error, invalid())?;
+            }
+            ("acceleration", "window") => {
+                let window = quantity(key, value, "s")?;
+                self.acceleration_window = check(window > Time::seconds(0), window, invalid())?;
+            }
+            ("acceleration", "filter") => {
+                let filter = quantity(key, value, "s")?;
+                self.acceleration_filter = check(filter >= Time::seconds(0.), filter, invalid())?;
+            }
+            ("panel", "permitted_speed") => self.panel.permitted_speed = panel(value)?,
+            ("panel", "target_speed" | "target_distance") => {
+                let digits = value
+                    .split(',')
+                    .map(|value| panel(value.trim()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if key == "target_speed" {
+                    self.panel.target_speed = digits;
+                } else {
+                    self.panel.target_distance = digits;
+                }
+            }
+            ("panel", "atc_lamp") => self.panel.atc_lamp = panel(value)?,
+            ("panel", "brake_lamp") => self.panel.brake_lamp = panel(value)?,
+            ("panel", "emergency_lamp") => self.panel.emergency_lamp = panel(value)?,
+            ("panel", "notice_lamp") => self.panel.notice_lamp = panel(value)?,
+            ("panel", "fault_lamp") => self.panel.fault_lamp = panel(value)?,
+            ("panel", "brake_release_lamp") => self.panel.brake_release_lamp = panel(value)?,
+            ("panel", "slow_order_lamp") => self.panel.slow_order_lamp = panel(value)?,
+            ("panel", "brake_reason") => self.panel.brake_reason = panel(value)?,
+            ("panel", "odometer_error") => self.panel.odometer_error = panel(value)?,
+            ("panel", "acceleration") => self.panel.acceleration = panel(value)?,
+            ("sound", "atc_bell") => self.sound.atc_bell = sound(value)?,
+            ("sound", "overspeed_alarm") => self.sound.overspeed_alarm = sound(value)?,
+            ("sound", "slow_order_chime") => self.sound.slow_order_chime = sound(value)?,
+            ("sound", "notice_chime") => self.sound.notice_chime = sound(value)?,
+            ("key", "mode") => self.keys.mode = Some(parse_key(value).ok_or_else(invalid)?),
+            _ => {
+                return Err(ConfigErrorKind::UnknownKey {
+                    section: section.to_owned(),
+                    key: key.to_owned(),
+                })
+            }
+        }
+        Ok(())
+    }
+}
+
+// A bare number is taken in `unit`, the unit the key was documented in before unit suffixes
+fn quantity<Q>(key: &str, value: &str, unit: &str) -> Result<Q, ConfigErrorKind>
+where
+    Q: FromStr<Err = ParseQuantityError>,
+{
+    let value = value.trim();
+    let parsed = if value.parse::<c_double>().is_ok() {
+        format!("{value}{unit}").parse()
+    } else {
+        value.parse()
+    };
+    parsed.map_err(|error| ConfigErrorKind::InvalidQuantity {
+        key: key.to_owned(),
+        value: value.to_owned(),
+        error,
+    })
+}
+
+fn check<T>(valid: bool, value: T, error: ConfigErrorKind) -> Result<T, ConfigErrorKind> {
+    if valid {
+        Ok(value)
+    } else {
+        Err(error)
+    }
+}
+
+// ATS key names as shown in the BVE key assignment
+fn parse_key(value: &str) -> Option<Key> {
+    Some(match value.to_ascii_uppercase().as_str() {
+        "S" => Key::S,
+        "A1" => Key::A1,
+        "A2" => Key::A2,
+        "B1" => Key::B1,
+        "B2" => Key::B2,
+        "C1" => Key::C1,
+        "C2" => Key::C2,
+        "D" => Key::D,
+        "E" => Key::E,
+        "F" => Key::F,
+        "G" => Key::G,
+        "H" => Key::H,
+        "I" => Key::I,
+        "J" => Key::J,
+        "K" => Key::K,
+        "L" => Key::L,
+        _ => return None,
+    })
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            ConfigErrorKind::Syntax => write!(f, "expected `[section]` or `key = value`"),
+            ConfigErrorKind::UnknownSection(section) => write!(f, "unknown section [{section}]"),
+            ConfigErrorKind::UnknownKey { section, key } => {
+                write!(f, "unknown key `{key}` in [{section}]")
+            }
+            ConfigErrorKind::InvalidValue { key, value } => {
+                write!(f, "invalid value `{value}` for `{key}`")
+            }
+            ConfigErrorKind::InvalidQuantity { key, value, error } => {
+                write!(f, "invalid value `{value}` for `{key}`: {error}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let (config, errors) = Config::parse(
+            "; KO-ATC settings\n\
+             [ATC]\n\
+             System = signal\n\
+             deceleration = 0.8m/s2\n\
+             notice_horizon = 3.5 ; s\n\
+             speed_codes = 0, 15km/h, 25\n\
+             [slow_order]\n\
+             order = 1.2km, 1500m, 25km/h\n\
+             order = 3000, 3100, 45\n\
+             [panel]\n\
+             target_speed = 30, 31\n\
+             [brake_check]\n\
+             applied_pressure = 2kgf/cm2\n\
+             apply_time = 2500ms\n\
+             [odometry]\n\
+             enabled = on\n\
+             wheel_error = -1.5%\n\
+             [acceleration]\n\
+             window = 0.3\n\
+             [key]\n\
+             mode = d\n",
+        );
+        assert_eq!(Vec::<ConfigError>::new(), errors);
+        assert_eq!(System::SignalCode, config.system);
+        assert_eq!(Acceleration::mps2(0.8), config.deceleration);
+        assert_eq!(Time::seconds(3.5), config.notice_horizon);
+        assert_eq!(
+            vec![
+                Velocity::kilometer_per_hour(0.),
+                Velocity::kilometer_per_hour(15.),
+                Velocity::kilometer_per_hour(25.)
+            ],
+            config.speed_codes
+        );
+        assert_eq!(2, config.slow_orders.len());
+        assert_eq!(
+            SlowOrder {
+                start: Length::meters(1200.),
+                end: Length::meters(1500.),
+                limit: Velocity::kilometer_per_hour(25.)
+            },
+            config.slow_orders[0]
+        );
+        assert_eq!(vec![PanelId(30), PanelId(31)], config.panel.target_speed);
+        assert_eq!(Some(Key::D), config.keys.mode);
+        assert_eq!(Pressure::kgf_per_cm2(2), config.applied_pressure);
+        assert_eq!(Time::milliseconds(2500), config.apply_time);
+        assert!(config.odometry);
+        assert_eq!(-0.015, config.wheel_error);
+        assert_eq!(Time::milliseconds(300), config.acceleration_window);
+        // Untouched settings keep their defaults
+        assert_eq!(Length::meters(20.), config.car_length);
+    }
+    #[test]
+    fn errors() {
+        let (config, errors) = Config::parse(
+            "[atc]\n\
+             deceleration = -1\n\
+             stop_window\n\
+             brake = 3\n\
+             hysteresis = 5kph\n\
+             [cab]\n\
+             [panel]\n\
+             atc_lamp = 256\n",
+        );
+        assert_eq!(
+            vec![
+                ConfigError {
+                    line: 2,
+                    kind: ConfigErrorKind::InvalidValue {
+                        key: "deceleration".to_owned(),
+                        value: "-1".to_owned()
+                    }
+                },
+                ConfigError {
+                    line: 3,
+                    kind: ConfigErrorKind::Syntax
+                },
+                ConfigError {
+                    line: 4,
+                    kind: ConfigErrorKind::UnknownKey {
+                        section: "atc".to_owned(),
+                        key: "brake".to_owned()
+                    }
+                },
+                ConfigError {
+                    line: 5,
+                    kind: ConfigErrorKind::InvalidQuantity {
+                        key: "hysteresis".to_owned(),
+                        value: "5kph".to_owned(),
+                        error: ParseQuantityError::UnknownUnit {
+                            unit: "kph".to_owned(),
+                            expected: &["km/h", "m/s"]
+                        }
+                    }
+                },
+                ConfigError {
+                    line: 6,
+                    kind: ConfigErrorKind::UnknownSection("cab".to_owned())
+                },
+                ConfigError {
+                    line: 8,
+                    kind: ConfigErrorKind::InvalidValue {
+                        key: "atc_lamp".to_owned(),
+                        value: "256".to_owned()
+                    }
+                },
+            ],
+            errors
+        );
+        assert_eq!(Acceleration::kmphps(2.5), config.deceleration);
+        assert_eq!(
+            "line 3: expected `[section]` or `key = value`",
+            errors[1].to_string()
+        );
+        assert_eq!(
+            "line 5: invalid value `5kph` for `hysteresis`: unknown unit `kph`, expected one of \
+             km/h, m/s",
+            errors[3].to_string()
+        );
+    }
+    #[test]
+    fn missing() {
+        let config = Config::load(Path::new("/nonexistent/bve_koatc.ini"));
+        assert_eq!(System::KoAtc, config.system);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::bve::{Length, Time, Velocity};
+use std::ffi::{c_double, c_int};
+
+// How the vehicle state moved since the previous frame
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Continuity {
+    // Also the first frame after a start
+    Normal,
+    JumpForward,
+    JumpBack,
+    // Time went backwards with the train where it could be
+    TimeRewind,
+    // Longer frame than any real tick, with the train where it could be
+    TimeSkip,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Frame {
+    location: Length<c_double>,
+    speed: Velocity,
+    time: Time<c_int>,
+}
+
+// Compares consecutive frames with the movement physically possible between them, to tell
+// station jumps, restarts and time changes from normal running.
+pub struct ContinuityChecker {
+    tolerance: Length<c_double>,
+    max_tick: Time<c_int>,
+    last: Option<Frame>,
+}
+
+impl ContinuityChecker {
+    pub fn new(tolerance: Length<c_double>, max_tick: Time<c_int>) -> Self {
+        Self {
+            tolerance,
+            max_tick,
+            last: None,
+        }
+    }
+    // The next frame starts afresh, e.g. after Initialize
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+    pub fn check(
+        &mut self,
+        location: Length<c_double>,
+        speed: Velocity,
+        time: Time<c_int>,
+    ) -> Continuity {
+        let frame = Frame {
+            location,
+            speed,
+            time,
+        };
+        let Some(last) = self.last.replace(frame) else {
+            return Continuity::Normal;
+        };
+        let elapsed = time - last.time;
+        let fastest = Velocity::mps(last.speed.as_mps().abs().max(speed.as_mps().abs()));
+        let running = if elapsed > Time::milliseconds(0) {
+            elapsed.as_::<c_double>()
+        } else {
+            Time::milliseconds(0.)
+        };
+        let reach = fastest * running + self.tolerance;
+        let moved = location - last.location;
+        if moved > reach {
+            Continuity::JumpForward
+        } else if moved < -reach {
+            Continuity::JumpBack
+        } else if elapsed < Time::milliseconds(0) {
+            Continuity::TimeRewind
+        } else if elapsed > self.max_tick {
+            Continuity::TimeSkip
+        } else {
+            Continuity::Normal
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checker() -> ContinuityChecker {
+        let mut checker = ContinuityChecker::new(Length::meters(5.), Time::seconds(2));
+        checker.check(
+            Length::meters(1000.),
+            Velocity::mps(20.),
+            Time::seconds(100),
+        );
+        checker
+    }
+
+    #[test]
+    fn normal() {
+        let mut checker = checker();
+        assert_eq!(
+            Continuity::Normal,
+            checker.check(
+                Length::meters(1020.),
+                Velocity::mps(20.),
+                Time::seconds(101)
+            )
+        );
+        // Zero-length frame
+        assert_eq!(
+            Continuity::Normal,
+            checker.check(
+                Length::meters(1020.),
+                Velocity::mps(20.),
+                Time::seconds(101)
+            )
+        );
+    }
+    #[test]
+    fn jump() {
+        let mut checker = checker();
+        assert_eq!(
+            Continuity::JumpForward,
+            checker.check(
+                Length::meters(1026.),
+                Velocity::mps(20.),
+                Time::seconds(101)
+            )
+        );
+        assert_eq!(
+            Continuity::JumpBack,
+            checker.check(Length::meters(0.), Velocity::mps(0.), Time::seconds(101))
+        );
+        // A station jump back usually moves the clock as well
+        assert_eq!(
+            Continuity::JumpBack,
+            checker.check(Length::meters(-500.), Velocity::mps(0.), Time::seconds(50))
+        );
+    }
+    #[test]
+    fn time() {
+        let mut checker = checker();
+        assert_eq!(
+            Continuity::TimeRewind,
+            checker.check(Length::meters(1000.), Velocity::mps(20.), Time::seconds(90))
+        );
+        assert_eq!(
+            Continuity::TimeSkip,
+            checker.check(Length::meters(1000.), Velocity::mps(0.), Time::seconds(95))
+        );
+    }
+    #[test]
+    fn reset() {
+        let mut checker = checker();
+        checker.reset();
+        assert_eq!(
+            Continuity::Normal,
+            checker.check(Length::meters(0.), Velocity::mps(0.), Time::seconds(0))
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use crate::bve::{Acceleration, Length, PanelId, PanelSound, Velocity};
+use std::ffi::{c_double, c_int};
+
+// Value written to a digit that is not shown: leading zeros, or no target at all.
+// Cab panels are expected to draw it as the blank 11th frame of the digit image.
+const BLANK_DIGIT: c_int = 10;
+
+// Panel indices the cab display is written to, so that one build fits several cab panels.
+// Digit displays list their panels from the most significant digit.
+#[derive(Clone, Debug)]
+pub struct PanelLayout {
+    pub permitted_speed: PanelId,
+    pub target_speed: Vec<PanelId>,
+    pub target_distance: Vec<PanelId>,
+    pub atc_lamp: PanelId,
+    pub brake_lamp: PanelId,
+    pub emergency_lamp: PanelId,
+    pub notice_lamp: PanelId,
+    pub fault_lamp: PanelId,
+    pub brake_release_lamp: PanelId,
+    pub slow_order_lamp: PanelId,
+    pub brake_reason: PanelId,
+    pub odometer_error: PanelId,
+    pub acceleration: PanelId,
+}
+
+impl Default for PanelLayout {
+    fn default() -> Self {
+        Self {
+            permitted_speed: PanelId(5),
+            target_speed: vec![PanelId(20), PanelId(21), PanelId(22)],
+            target_distance: vec![PanelId(23), PanelId(24), PanelId(25), PanelId(26)],
+            atc_lamp: PanelId(1),
+            brake_lamp: PanelId(2),
+            emergency_lamp: PanelId(3),
+            notice_lamp: PanelId(4),
+            fault_lamp: PanelId(9),
+            brake_release_lamp: PanelId(12),
+            slow_order_lamp: PanelId(11),
+            brake_reason: PanelId(10),
+            odometer_error: PanelId(13),
+            acceleration: PanelId(14),
+        }
+    }
+}
+
+// Everything the cab display shows for one frame
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CabIndication {
+    // None leaves the speedometer ring dark
+    pub permitted_speed: Option<Velocity>,
+    pub target_speed: Option<Velocity>,
+    pub target_distance: Option<Length<c_double>>,
+    pub atc: bool,
+    pub brake: bool,
+    pub emergency: bool,
+    pub notice: bool,
+    pub fault: bool,
+    pub brake_not_released: bool,
+    pub slow_order: bool,
+    // BrakeReason of the last brake transition, or 0
+    pub brake_reason: c_int,
+    // Odometer minus true location; zero without odometry. Written in centimetres.
+    pub odometer_error: Length<c_double>,
+    // Estimated from the speed. Written in 0.1 km/h/s.
+    pub acceleration: Acceleration,
+}
+
+impl PanelLayout {
+    pub fn write(&self, indication: &CabIndication, output: &mut PanelSound) {
+        let kmph = |speed: Velocity| speed.as_kmph().round() as c_int;
+        output.set_panel(
+            self.permitted_speed,
+            indication.permitted_speed.map_or(0, kmph),
+        );
+        write_digits(
+            &self.target_speed,
+            indication.target_speed.map(kmph),
+            output,
+        );
+        write_digits(
+            &self.target_distance,
+            indication
+                .target_distance
+                .map(|distance| distance.as_meters().max(0.).round() as c_int),
+            output,
+        );
+        let lamps = [
+            (self.atc_lamp, indication.atc),
+            (self.brake_lamp, indication.brake),
+            (self.emergency_lamp, indication.emergency),
+            (self.notice_lamp, indication.notice),
+            (self.fault_lamp, indication.fault),
+            (self.brake_release_lamp, indication.brake_not_released),
+            (self.slow_order_lamp, indication.slow_order),
+        ];
+        for (panel, lit) in lamps {
+            output.set_panel(panel, lit as c_int);
+        }
+        output.set_panel(self.brake_reason, indication.brake_reason);
+        output.set_panel(
+            self.odometer_error,
+            (indication.odometer_error.as_meters() * 100.).round() as c_int,
+        );
+        output.set_panel(
+            self.acceleration,
+            (indication.acceleration.as_kmphps() * 10.).round() as c_int,
+        );
+    }
+}
+
+// A value too large for the display shows all nines
+fn write_digits(panels: &[PanelId], value: Option<c_int>, output: &mut PanelSound) {
+    for (panel, digit) in panels.iter().zip(digits(value, panels.len())) {
+        output.set_panel(*panel, digit);
+    }
+}
+
+fn digits(value: Option<c_int>, count: usize) -> Vec<c_int> {
+    let Some(value) = value else {
+        return vec![BLANK_DIGIT; count];
+    };
+    // Places beyond what an i64 holds stay blank, however long the panel list is
+    let max = 10_i64
+        .checked_pow(count as u32)
+        .map_or(i64::MAX, |scale| scale - 1);
+    let value = (value as i64).clamp(0, max);
+    (0..count)
+        .rev()
+        .map(|place| match 10_i64.checked_pow(place as u32) {
+            Some(scale) if place == 0 || value >= scale => (value / scale % 10) as c_int,
+            _ => BLANK_DIGIT,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits() {
+        assert_eq!(vec![BLANK_DIGIT, 4, 5], super::digits(Some(45), 3));
+        assert_eq!(vec![BLANK_DIGIT, BLANK_DIGIT, 0], super::digits(Some(0), 3));
+        assert_eq!(vec![9, 9, 9], super::digits(Some(1200), 3));
+        assert_eq!(vec![BLANK_DIGIT; 3], super::digits(None, 3));
+        let long = super::digits(Some(c_int::MAX), 24);
+        assert_eq!(vec![BLANK_DIGIT; 14], long[..14]);
+        assert_eq!(vec![2, 1, 4, 7, 4, 8, 3, 6, 4, 7], long[14..]);
+    }
+    #[test]
+    fn write() {
+        let mut panel = [0; 256];
+        let mut sound = [0; 256];
+        let layout = PanelLayout::default();
+        layout.write(
+            &CabIndication {
+                permitted_speed: Some(Velocity::kilometer_per_hour(75.)),
+                target_speed: Some(Velocity::kilometer_per_hour(25.)),
+                target_distance: Some(Length::meters(1234.4)),
+                atc: true,
+                brake: false,
+                emergency: false,
+                notice: true,
+                fault: false,
+                brake_not_released: false,
+                slow_order: false,
+                brake_reason: 0,
+                odometer_error: Length::meters(-1.25),
+                acceleration: Acceleration::kmphps(-2.5),
+            },
+            &mut PanelSound::new(&mut panel, &mut sound),
+        );
+        assert_eq!(75, panel[5]);
+        assert_eq!([BLANK_DIGIT, 2, 5], panel[20..23]);
+        assert_eq!([1, 2, 3, 4], panel[23..27]);
+        assert_eq!([1, 0, 0, 1], panel[1..5]);
+        assert_eq!([-125, -25], panel[13..15]);
+    }
+}
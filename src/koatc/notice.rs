@@ -0,0 +1,125 @@
+use crate::bve::{Length, Time, Velocity};
+use crate::koatc::target::{Target, TargetSource, TargetStore};
+use std::ffi::c_double;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Notice {
+    pub target: Option<Target>,
+    // A target not announced before has just come into notice
+    pub chime: bool,
+}
+
+// Advance notice (予告) of a lower target, given `horizon` before its curve starts to limit
+// the train so that the driver can brake ahead of ATC.
+// Each target is announced once, even if the notice goes off and on again for it or another
+// target takes turns with it.
+pub struct AdvanceNotice {
+    horizon: Time<c_double>,
+    // Targets announced and still in the store
+    announced: Vec<(TargetSource, Length<c_double>)>,
+}
+
+impl AdvanceNotice {
+    pub fn new(horizon: Time<c_double>) -> Self {
+        Self {
+            horizon,
+            announced: Vec::new(),
+        }
+    }
+    pub fn clear(&mut self) {
+        self.announced.clear();
+    }
+    pub fn update(
+        &mut self,
+        targets: &TargetStore,
+        location: Length<c_double>,
+        speed: Velocity,
+    ) -> Notice {
+        self.announced
+            .retain(|&(source, location)| targets.contains(source, location));
+        let target = targets.upcoming(location, speed, self.horizon);
+        let chime = match target.map(|target| (target.source, target.location)) {
+            Some(key) if !self.announced.contains(&key) => {
+                self.announced.push(key);
+                true
+            }
+            _ => false,
+        };
+        Notice { target, chime }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bve::Acceleration;
+    use crate::koatc::pattern::BrakingCurve;
+
+    fn store() -> TargetStore {
+        TargetStore::new(
+            BrakingCurve::new(Acceleration::mps2(1.)),
+            Length::meters(10.),
+        )
+    }
+
+    #[test]
+    fn chime_once() {
+        let mut targets = store();
+        let target = Target {
+            location: Length::meters(1000.),
+            speed: Velocity::mps(5.),
+            source: TargetSource::Restriction(0),
+        };
+        targets.insert(target);
+        let mut notice = AdvanceNotice::new(Time::seconds(8.));
+        let speed = Velocity::mps(13.);
+        let update = |notice: &mut AdvanceNotice, location: f64, speed: Velocity| {
+            notice.update(&targets, Length::meters(location), speed)
+        };
+        assert_eq!(
+            Notice {
+                target: None,
+                chime: false
+            },
+            update(&mut notice, 800., speed)
+        );
+        assert_eq!(
+            Notice {
+                target: Some(target),
+                chime: true
+            },
+            update(&mut notice, 850., speed)
+        );
+        assert!(!update(&mut notice, 860., speed).chime);
+        // Slowed down below the target speed and back up again
+        assert_eq!(None, update(&mut notice, 870., Velocity::mps(4.)).target);
+        assert!(!update(&mut notice, 880., speed).chime);
+    }
+    #[test]
+    fn taking_turns() {
+        let mut targets = store();
+        let restriction = Target {
+            location: Length::meters(1000.),
+            speed: Velocity::mps(10.),
+            source: TargetSource::Restriction(0),
+        };
+        let station = Target {
+            location: Length::meters(1100.),
+            speed: Velocity::mps(0.),
+            source: TargetSource::StationStop,
+        };
+        targets.insert(restriction);
+        targets.insert(station);
+        let mut notice = AdvanceNotice::new(Time::seconds(20.));
+        let mut update = |location: f64, speed: f64| {
+            notice.update(&targets, Length::meters(location), Velocity::mps(speed))
+        };
+        // The restriction is out of notice whenever the train is below its speed
+        let first = update(900., 13.);
+        assert_eq!((Some(restriction), true), (first.target, first.chime));
+        let second = update(910., 9.);
+        assert_eq!((Some(station), true), (second.target, second.chime));
+        assert!(!update(920., 13.).chime);
+        assert!(!update(930., 9.).chime);
+    }
+}
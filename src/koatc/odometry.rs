@@ -0,0 +1,108 @@
+use crate::bve::{Length, Time, Velocity};
+use std::ffi::{c_double, c_int};
+
+// Location as the train measures it: wheel rotation counted from the last position
+// correction, so a worn or mismeasured wheel makes it drift from the kilometre post.
+pub struct Odometer {
+    // Measured distance per distance run; 1 + relative wheel-diameter error
+    scale: c_double,
+    position: Option<Length<c_double>>,
+    last: Option<(Velocity, Time<c_int>)>,
+    correction_pending: bool,
+}
+
+impl Odometer {
+    // `wheel_error` is how far the measured distance reads long, e.g. 0.01 for 1% as with a
+    // wheel worn below the diameter the ATC was set up for
+    pub fn new(wheel_error: c_double) -> Self {
+        Self {
+            scale: 1. + wheel_error,
+            position: None,
+            last: None,
+            correction_pending: false,
+        }
+    }
+    // The next frame takes the true location again, e.g. after a location jump
+    pub fn reset(&mut self) {
+        self.position = None;
+        self.last = None;
+        self.correction_pending = false;
+    }
+    // A position-correction coil was passed; the location of the next frame is exact
+    pub fn correct(&mut self) {
+        self.correction_pending = true;
+    }
+    // Returns the measured location. `location` is only used on the first frame and for
+    // corrections.
+    pub fn update(
+        &mut self,
+        location: Length<c_double>,
+        speed: Velocity,
+        time: Time<c_int>,
+    ) -> Length<c_double> {
+        let position = match (self.position, self.last) {
+            (Some(_), _) if self.correction_pending => location,
+            // Mean of both frames' speeds over a frame; a rewound clock counts nothing
+            (Some(position), Some((last_speed, last_time))) if time > last_time => {
+                let elapsed = (time - last_time).as_::<c_double>();
+                position + (last_speed + speed) / 2. * elapsed * self.scale
+            }
+            (Some(position), _) => position,
+            (None, _) => location,
+        };
+        self.position = Some(position);
+        self.last = Some((speed, time));
+        self.correction_pending = false;
+        position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drift() {
+        let mut odometer = Odometer::new(0.01);
+        let speed = Velocity::mps(20.);
+        assert_eq!(
+            Length::meters(1000.),
+            odometer.update(Length::meters(1000.), speed, Time::seconds(0))
+        );
+        let measured = odometer.update(Length::meters(1200.), speed, Time::seconds(10));
+        assert!((measured.as_meters() - 1202.).abs() < 1e-6);
+        // Accelerating from rest over one frame covers half the distance of the final speed
+        let mut odometer = Odometer::new(0.);
+        odometer.update(Length::meters(0.), Velocity::mps(0.), Time::seconds(0));
+        let measured = odometer.update(Length::meters(5.), Velocity::mps(2.), Time::seconds(5));
+        assert!((measured.as_meters() - 5.).abs() < 1e-6);
+    }
+    #[test]
+    fn correct() {
+        let mut odometer = Odometer::new(-0.02);
+        let speed = Velocity::mps(10.);
+        odometer.update(Length::meters(0.), speed, Time::seconds(0));
+        odometer.correct();
+        assert_eq!(
+            Length::meters(100.),
+            odometer.update(Length::meters(100.), speed, Time::seconds(10))
+        );
+        let measured = odometer.update(Length::meters(200.), speed, Time::seconds(20));
+        assert!((measured.as_meters() - 198.).abs() < 1e-6);
+    }
+    #[test]
+    fn rewind() {
+        let mut odometer = Odometer::new(0.);
+        let speed = Velocity::mps(10.);
+        odometer.update(Length::meters(0.), speed, Time::seconds(10));
+        assert_eq!(
+            Length::meters(0.),
+            odometer.update(Length::meters(0.), speed, Time::seconds(5))
+        );
+        odometer.reset();
+        assert_eq!(
+            Length::meters(500.),
+            odometer.update(Length::meters(500.), speed, Time::seconds(6))
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use crate::bve::{Acceleration, Length, Velocity};
+use std::ffi::c_double;
+
+// Single-step braking curve with a constant deceleration.
+// The allowed speed at a location is the speed from which the train can still slow down
+// to the target speed by the time it reaches the target.
+#[derive(Copy, Clone, Debug)]
+pub struct BrakingCurve {
+    deceleration: Acceleration,
+}
+
+impl BrakingCurve {
+    pub fn new(deceleration: Acceleration) -> Self {
+        Self { deceleration }
+    }
+    pub fn allowed_speed(
+        &self,
+        location: Length<c_double>,
+        target: Length<c_double>,
+        target_speed: Velocity,
+    ) -> Velocity {
+        let remaining = target - location;
+        if remaining <= Length::meters(0.) {
+            return target_speed;
+        }
+        target_speed.speed_at_distance(-self.deceleration, remaining)
+    }
+    // Distance before the target at which the curve comes down to `speed`
+    pub fn braking_distance(&self, speed: Velocity, target_speed: Velocity) -> Length<c_double> {
+        if speed <= target_speed {
+            return Length::meters(0.);
+        }
+        speed.braking_distance(self.deceleration) - target_speed.braking_distance(self.deceleration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stop() {
+        let curve = BrakingCurve::new(Acceleration::mps2(1.));
+        let target = Length::meters(1200.);
+        let stop = Velocity::mps(0.);
+        assert_eq!(
+            Velocity::mps(20.),
+            curve.allowed_speed(Length::meters(1000.), target, stop)
+        );
+        assert_eq!(
+            Velocity::mps(10.),
+            curve.allowed_speed(Length::meters(1150.), target, stop)
+        );
+        assert_eq!(
+            Velocity::mps(0.),
+            curve.allowed_speed(Length::meters(1200.), target, stop)
+        );
+        assert_eq!(
+            Velocity::mps(0.),
+            curve.allowed_speed(Length::meters(1300.), target, stop)
+        );
+    }
+    #[test]
+    fn slow_down() {
+        let curve = BrakingCurve::new(Acceleration::mps2(1.));
+        let target = Length::meters(1000.);
+        assert_eq!(
+            Velocity::mps(5.),
+            curve.allowed_speed(Length::meters(1000.), target, Velocity::mps(5.))
+        );
+        assert_eq!(
+            Velocity::mps(13.),
+            curve.allowed_speed(Length::meters(928.), target, Velocity::mps(5.))
+        );
+    }
+    #[test]
+    fn braking_distance() {
+        let curve = BrakingCurve::new(Acceleration::mps2(1.));
+        assert_eq!(
+            Length::meters(72.),
+            curve.braking_distance(Velocity::mps(13.), Velocity::mps(5.))
+        );
+        assert_eq!(
+            Length::meters(0.),
+            curve.braking_distance(Velocity::mps(5.), Velocity::mps(13.))
+        );
+    }
+}
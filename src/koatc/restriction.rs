@@ -0,0 +1,140 @@
+use crate::bve::{Length, Velocity};
+use crate::koatc::target::{Target, TargetSource};
+use std::ffi::c_double;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct SpeedZone {
+    start: Length<c_double>,
+    end: Length<c_double>,
+    limit: Velocity,
+}
+
+// Permanent speed limits for curves and turnouts.
+// A zone stays in force until the tail of the train has cleared its end, so the limit is
+// measured against the whole train and not just the head. The approach to a zone is left to
+// the target store.
+pub struct SpeedRestrictions {
+    train_length: Length<c_double>,
+    zones: Vec<SpeedZone>,
+    // Zones started so far, to number their targets
+    started: usize,
+}
+
+impl SpeedRestrictions {
+    pub fn new() -> Self {
+        Self {
+            train_length: Length::meters(0.),
+            zones: Vec::new(),
+            started: 0,
+        }
+    }
+    pub fn set_train_length(&mut self, train_length: Length<c_double>) {
+        self.train_length = train_length;
+    }
+    // Zone starting at `location` and spanning `length`; returns the target that brakes the
+    // train into it
+    pub fn start(
+        &mut self,
+        location: Length<c_double>,
+        limit: Velocity,
+        length: Length<c_double>,
+    ) -> Target {
+        self.zones.push(SpeedZone {
+            start: location,
+            end: location + length,
+            limit,
+        });
+        self.started += 1;
+        Target {
+            location,
+            speed: limit,
+            source: TargetSource::Restriction(self.started - 1),
+        }
+    }
+    // Cuts every zone the head is in short at `location`
+    pub fn end(&mut self, location: Length<c_double>) {
+        for zone in &mut self.zones {
+            if zone.start <= location && location < zone.end {
+                zone.end = location;
+            }
+        }
+    }
+    pub fn clear(&mut self) {
+        self.zones.clear();
+    }
+    // Drops zones the tail has cleared
+    pub fn expire(&mut self, location: Length<c_double>) {
+        let tail = location - self.train_length;
+        self.zones.retain(|zone| tail <= zone.end);
+    }
+    // Lowest limit of the zones any part of the train is in
+    pub fn limit(&self, location: Length<c_double>) -> Option<Velocity> {
+        let tail = location - self.train_length;
+        self.zones
+            .iter()
+            .filter(|zone| zone.start <= location && tail <= zone.end)
+            .map(|zone| zone.limit)
+            .min_by(|a, b| a.as_mps().total_cmp(&b.as_mps()))
+    }
+}
+
+impl Default for SpeedRestrictions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn restrictions() -> SpeedRestrictions {
+        let mut restrictions = SpeedRestrictions::new();
+        restrictions.set_train_length(Length::meters(200.));
+        restrictions
+    }
+
+    #[test]
+    fn released_by_tail() {
+        let mut restrictions = restrictions();
+        let limit = Velocity::kilometer_per_hour(45.);
+        restrictions.start(Length::meters(1000.), limit, Length::meters(300.));
+        assert_eq!(Some(limit), restrictions.limit(Length::meters(1000.)));
+        assert_eq!(Some(limit), restrictions.limit(Length::meters(1400.)));
+        assert_eq!(Some(limit), restrictions.limit(Length::meters(1500.)));
+        restrictions.expire(Length::meters(1500.));
+        assert_eq!(Some(limit), restrictions.limit(Length::meters(1500.)));
+        restrictions.expire(Length::meters(1500.1));
+        assert_eq!(None, restrictions.limit(Length::meters(1500.1)));
+    }
+    #[test]
+    fn most_restrictive() {
+        let mut restrictions = restrictions();
+        let curve = Velocity::kilometer_per_hour(60.);
+        let turnout = Velocity::kilometer_per_hour(35.);
+        let first = restrictions.start(Length::meters(1000.), curve, Length::meters(500.));
+        let second = restrictions.start(Length::meters(1100.), turnout, Length::meters(50.));
+        assert_eq!(
+            Target {
+                location: Length::meters(1100.),
+                speed: turnout,
+                source: TargetSource::Restriction(1)
+            },
+            second
+        );
+        assert_ne!(first.source, second.source);
+        assert_eq!(Some(curve), restrictions.limit(Length::meters(1050.)));
+        assert_eq!(Some(turnout), restrictions.limit(Length::meters(1100.)));
+        assert_eq!(Some(turnout), restrictions.limit(Length::meters(1350.)));
+        assert_eq!(Some(curve), restrictions.limit(Length::meters(1351.)));
+    }
+    #[test]
+    fn end() {
+        let mut restrictions = restrictions();
+        let limit = Velocity::kilometer_per_hour(45.);
+        restrictions.start(Length::meters(1000.), limit, Length::meters(9000.));
+        restrictions.end(Length::meters(1100.));
+        assert_eq!(Some(limit), restrictions.limit(Length::meters(1300.)));
+        assert_eq!(None, restrictions.limit(Length::meters(1301.)));
+    }
+}
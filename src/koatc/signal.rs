@@ -0,0 +1,122 @@
+use crate::bve::{Length, Velocity};
+use crate::koatc::target::{Target, TargetSource};
+use std::ffi::{c_double, c_int};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+struct NextSection {
+    start: Length<c_double>,
+    code: Velocity,
+}
+
+// ATC speed codes for routes that only provide section signal indices.
+// The code of the current section comes from SetSignal; the code of the next section is
+// pre-loaded from the `signal`/`distance` of passing beacons and given to the target store,
+// so that the train is braked into it rather than at the boundary.
+// Signal indices outside the table give the stop code.
+pub struct SignalCode {
+    table: Vec<Velocity>,
+    current: Option<Velocity>,
+    next: Option<NextSection>,
+}
+
+impl SignalCode {
+    pub fn new(table: Vec<Velocity>) -> Self {
+        Self {
+            table,
+            current: None,
+            next: None,
+        }
+    }
+    pub fn code(&self, signal: c_int) -> Velocity {
+        usize::try_from(signal)
+            .ok()
+            .and_then(|signal| self.table.get(signal))
+            .copied()
+            .unwrap_or(Velocity::mps(0.))
+    }
+    pub fn set_signal(&mut self, signal: c_int) {
+        self.current = Some(self.code(signal));
+    }
+    pub fn preload(&mut self, signal: c_int, start: Length<c_double>) {
+        self.next = Some(NextSection {
+            start,
+            code: self.code(signal),
+        });
+    }
+    pub fn clear(&mut self) {
+        self.current = None;
+        self.next = None;
+    }
+    // The pre-loaded code takes over once the head enters the next section
+    pub fn update(&mut self, location: Length<c_double>) {
+        if let Some(next) = self.next.filter(|next| next.start <= location) {
+            self.current = Some(next.code);
+            self.next = None;
+        }
+    }
+    // Code of the section the train is in; None until the first signal is known
+    pub fn limit(&self) -> Option<Velocity> {
+        self.current
+    }
+    // Boundary of the pre-loaded section, if the train has not entered it yet
+    pub fn target(&self) -> Option<Target> {
+        self.next.map(|next| Target {
+            location: next.start,
+            speed: next.code,
+            source: TargetSource::SignalCode,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal() -> SignalCode {
+        SignalCode::new(vec![
+            Velocity::mps(0.),
+            Velocity::mps(5.),
+            Velocity::mps(15.),
+        ])
+    }
+
+    #[test]
+    fn code() {
+        let signal = signal();
+        assert_eq!(Velocity::mps(0.), signal.code(0));
+        assert_eq!(Velocity::mps(15.), signal.code(2));
+        assert_eq!(Velocity::mps(0.), signal.code(3));
+        assert_eq!(Velocity::mps(0.), signal.code(-1));
+    }
+    #[test]
+    fn current() {
+        let mut signal = signal();
+        assert_eq!(None, signal.limit());
+        signal.set_signal(2);
+        assert_eq!(Some(Velocity::mps(15.)), signal.limit());
+        signal.set_signal(1);
+        assert_eq!(Some(Velocity::mps(5.)), signal.limit());
+    }
+    #[test]
+    fn preload() {
+        let mut signal = signal();
+        signal.set_signal(2);
+        signal.preload(1, Length::meters(1000.));
+        assert_eq!(
+            Some(Target {
+                location: Length::meters(1000.),
+                speed: Velocity::mps(5.),
+                source: TargetSource::SignalCode
+            }),
+            signal.target()
+        );
+        signal.update(Length::meters(999.));
+        assert_eq!(Some(Velocity::mps(15.)), signal.limit());
+        signal.update(Length::meters(1000.));
+        assert_eq!(Some(Velocity::mps(5.)), signal.limit());
+        assert_eq!(None, signal.target());
+        // SetSignal for the new section overrides the pre-loaded code
+        signal.set_signal(2);
+        assert_eq!(Some(Velocity::mps(15.)), signal.limit());
+    }
+}
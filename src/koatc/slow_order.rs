@@ -0,0 +1,180 @@
+use crate::bve::{Length, Velocity};
+use crate::koatc::target::{Target, TargetSource};
+use std::ffi::c_double;
+
+// Ends of the same zone received again differ by where in the frame the beacon was passed
+// and by odometer drift; half the 10 m resolution of the telegram
+const SAME_ZONE_TOLERANCE: c_double = 5.;
+
+// Temporary speed restriction (徐行) for track work, in absolute route locations
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SlowOrder {
+    pub start: Length<c_double>,
+    pub end: Length<c_double>,
+    pub limit: Velocity,
+}
+
+impl SlowOrder {
+    fn same_zone(&self, other: &SlowOrder) -> bool {
+        let near = |a: Length<c_double>, b: Length<c_double>| {
+            (a - b).as_meters().abs() <= SAME_ZONE_TOLERANCE
+        };
+        self.limit == other.limit && near(self.start, other.start) && near(self.end, other.end)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SlowOrderStatus {
+    // Lowest of the zones the train is in; zones ahead are targets in the target store
+    pub limit: Option<Velocity>,
+    // The train is in a zone or within the warning distance of one
+    pub indicated: bool,
+    // The head has just come within the warning distance of a zone
+    pub warn: bool,
+}
+
+struct Zone {
+    order: SlowOrder,
+    warned: bool,
+}
+
+// Slow orders are kept for the whole session, so they stay in force after a station jump.
+// Being absolute, they are also re-armed for warning when the train jumps back before them.
+pub struct SlowOrders {
+    warning_distance: Length<c_double>,
+    train_length: Length<c_double>,
+    zones: Vec<Zone>,
+}
+
+impl SlowOrders {
+    pub fn new(warning_distance: Length<c_double>) -> Self {
+        Self {
+            warning_distance,
+            train_length: Length::meters(0.),
+            zones: Vec::new(),
+        }
+    }
+    pub fn set_train_length(&mut self, train_length: Length<c_double>) {
+        self.train_length = train_length;
+    }
+    // The same order received twice, e.g. from a beacon passed again, is kept once. Returns
+    // the target that brakes the train into a new zone.
+    pub fn add(&mut self, order: SlowOrder) -> Option<Target> {
+        if self.zones.iter().any(|zone| zone.order.same_zone(&order)) {
+            return None;
+        }
+        self.zones.push(Zone {
+            order,
+            warned: false,
+        });
+        self.targets().last()
+    }
+    // Targets of every zone, to restore them after the target store was cleared
+    pub fn targets(&self) -> impl Iterator<Item = Target> + '_ {
+        self.zones.iter().enumerate().map(|(index, zone)| Target {
+            location: zone.order.start,
+            speed: zone.order.limit,
+            source: TargetSource::SlowOrder(index),
+        })
+    }
+    pub fn update(&mut self, location: Length<c_double>) -> SlowOrderStatus {
+        let tail = location - self.train_length;
+        let mut status = SlowOrderStatus {
+            limit: None,
+            indicated: false,
+            warn: false,
+        };
+        for zone in &mut self.zones {
+            let order = zone.order;
+            if tail > order.end {
+                continue;
+            }
+            if location >= order.start && status.limit.is_none_or(|limit| order.limit < limit) {
+                status.limit = Some(order.limit);
+            }
+            let warning = order.start - self.warning_distance;
+            if location < warning {
+                zone.warned = false;
+                continue;
+            }
+            status.indicated = true;
+            if !zone.warned && location < order.start {
+                status.warn = true;
+            }
+            zone.warned = true;
+        }
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orders() -> SlowOrders {
+        let mut orders = SlowOrders::new(Length::meters(500.));
+        orders.set_train_length(Length::meters(100.));
+        orders.add(SlowOrder {
+            start: Length::meters(1000.),
+            end: Length::meters(1200.),
+            limit: Velocity::mps(5.),
+        });
+        orders
+    }
+
+    #[test]
+    fn limit() {
+        let mut orders = orders();
+        assert_eq!(None, orders.update(Length::meters(999.)).limit);
+        assert_eq!(
+            Some(Velocity::mps(5.)),
+            orders.update(Length::meters(1000.)).limit
+        );
+        assert_eq!(
+            Some(Velocity::mps(5.)),
+            orders.update(Length::meters(1300.)).limit
+        );
+        assert_eq!(None, orders.update(Length::meters(1300.1)).limit);
+    }
+    #[test]
+    fn warning() {
+        let mut orders = orders();
+        let status = orders.update(Length::meters(400.));
+        assert!(!status.indicated);
+        assert!(!status.warn);
+        let status = orders.update(Length::meters(500.));
+        assert!(status.indicated);
+        assert!(status.warn);
+        let status = orders.update(Length::meters(600.));
+        assert!(status.indicated);
+        assert!(!status.warn);
+        // Station jump back before the zone re-arms the warning
+        orders.update(Length::meters(0.));
+        assert!(orders.update(Length::meters(550.)).warn);
+    }
+    #[test]
+    fn duplicate() {
+        let mut orders = orders();
+        // Received again after a jump back, measured from a slightly different location
+        let duplicate = orders.add(SlowOrder {
+            start: Length::meters(1003.2),
+            end: Length::meters(1203.2),
+            limit: Velocity::mps(5.),
+        });
+        assert_eq!(None, duplicate);
+        assert_eq!(1, orders.zones.len());
+        let target = orders.add(SlowOrder {
+            start: Length::meters(3000.),
+            end: Length::meters(3100.),
+            limit: Velocity::mps(10.),
+        });
+        assert_eq!(
+            Some(Target {
+                location: Length::meters(3000.),
+                speed: Velocity::mps(10.),
+                source: TargetSource::SlowOrder(1)
+            }),
+            target
+        );
+    }
+}
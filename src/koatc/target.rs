@@ -0,0 +1,248 @@
+use crate::bve::{Length, Time, Velocity};
+use crate::koatc::pattern::BrakingCurve;
+use std::ffi::{c_double, c_int};
+
+// Where a forward target came from; a new target replaces the one with the same source
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum TargetSource {
+    StopTarget(c_int),
+    StationStop,
+    // Start of a permanent speed restriction, by zone number
+    Restriction(usize),
+    // Start of a slow order, by order number
+    SlowOrder(usize),
+    // Boundary of the next signal section
+    SignalCode,
+}
+
+impl TargetSource {
+    // The train has to stop short of the target, rather than slow down for a zone beyond it
+    pub fn is_stop(&self) -> bool {
+        matches!(
+            self,
+            TargetSource::StopTarget(_) | TargetSource::StationStop
+        )
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Target {
+    pub location: Length<c_double>,
+    pub speed: Velocity,
+    pub source: TargetSource,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Envelope {
+    pub allowed_speed: Velocity,
+    // The target whose braking curve is the lowest at the current location
+    pub limiting: Target,
+}
+
+// Constraints ahead of the train.
+// Zone and section targets are dropped once the head passes them, where the limit of the
+// zone itself takes over. Stop targets stay until the ground clears them, except a station
+// stop which is done once the train stands within `stop_window` of it.
+pub struct TargetStore {
+    curve: BrakingCurve,
+    stop_window: Length<c_double>,
+    targets: Vec<Target>,
+}
+
+impl TargetStore {
+    pub fn new(curve: BrakingCurve, stop_window: Length<c_double>) -> Self {
+        Self {
+            curve,
+            stop_window,
+            targets: Vec::new(),
+        }
+    }
+    pub fn insert(&mut self, target: Target) {
+        self.remove(target.source);
+        self.targets.push(target);
+    }
+    pub fn remove(&mut self, source: TargetSource) {
+        self.targets.retain(|target| target.source != source);
+    }
+    pub fn clear(&mut self) {
+        self.targets.clear();
+    }
+    pub fn contains(&self, source: TargetSource, location: Length<c_double>) -> bool {
+        self.targets
+            .iter()
+            .any(|target| target.source == source && target.location == location)
+    }
+    pub fn expire(&mut self, location: Length<c_double>, speed: Velocity) {
+        let stopped = speed <= Velocity::mps(0.);
+        let stop_window = self.stop_window;
+        self.targets.retain(|target| match target.source {
+            TargetSource::StopTarget(_) => true,
+            TargetSource::StationStop => !(stopped && target.location - location <= stop_window),
+            _ => location <= target.location,
+        });
+    }
+    // A stop target behind the head of the train
+    pub fn overrun(&self, location: Length<c_double>) -> bool {
+        self.targets
+            .iter()
+            .any(|target| target.source.is_stop() && location > target.location)
+    }
+    // Lower target whose curve the train meets within `horizon` at its current speed; the
+    // soonest one if there are several
+    pub fn upcoming(
+        &self,
+        location: Length<c_double>,
+        speed: Velocity,
+        horizon: Time<c_double>,
+    ) -> Option<Target> {
+        self.targets
+            .iter()
+            .filter(|target| target.speed < speed && location < target.location)
+            .map(|&target| {
+                let onset = target.location - self.curve.braking_distance(speed, target.speed);
+                (target, (onset - location) / speed)
+            })
+            .filter(|&(_, time)| time <= horizon)
+            .min_by(|(_, a), (_, b)| a.as_seconds().total_cmp(&b.as_seconds()))
+            .map(|(target, _)| target)
+    }
+    // None while there is no target ahead
+    pub fn envelope(&self, location: Length<c_double>) -> Option<Envelope> {
+        self.targets
+            .iter()
+            .map(|&target| Envelope {
+                allowed_speed: self
+                    .curve
+                    .allowed_speed(location, target.location, target.speed),
+                limiting: target,
+            })
+            .min_by(|a, b| {
+                a.allowed_speed
+                    .as_mps()
+                    .total_cmp(&b.allowed_speed.as_mps())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bve::Acceleration;
+
+    fn store() -> TargetStore {
+        TargetStore::new(
+            BrakingCurve::new(Acceleration::mps2(1.)),
+            Length::meters(10.),
+        )
+    }
+    fn target(location: f64, speed: f64, source: TargetSource) -> Target {
+        Target {
+            location: Length::meters(location),
+            speed: Velocity::mps(speed),
+            source,
+        }
+    }
+
+    #[test]
+    fn envelope() {
+        let mut store = store();
+        assert_eq!(None, store.envelope(Length::meters(0.)));
+        let signal = target(1000., 0., TargetSource::StopTarget(1));
+        let station = target(500., 0., TargetSource::StationStop);
+        store.insert(signal);
+        store.insert(station);
+        assert_eq!(
+            Some(Envelope {
+                allowed_speed: Velocity::mps(20.),
+                limiting: station
+            }),
+            store.envelope(Length::meters(300.))
+        );
+        store.remove(TargetSource::StationStop);
+        assert_eq!(
+            Some(Envelope {
+                allowed_speed: Velocity::mps(40.),
+                limiting: signal
+            }),
+            store.envelope(Length::meters(200.))
+        );
+    }
+    #[test]
+    fn replace() {
+        let mut store = store();
+        store.insert(target(1000., 0., TargetSource::StopTarget(1)));
+        store.insert(target(1200., 0., TargetSource::StopTarget(1)));
+        let envelope = store.envelope(Length::meters(1000.)).unwrap();
+        assert_eq!(Velocity::mps(20.), envelope.allowed_speed);
+    }
+    #[test]
+    fn expire() {
+        let mut store = store();
+        store.insert(target(1000., 0., TargetSource::StopTarget(1)));
+        store.insert(target(500., 0., TargetSource::StationStop));
+        store.expire(Length::meters(480.), Velocity::mps(3.));
+        assert_eq!(
+            Some(TargetSource::StationStop),
+            store
+                .envelope(Length::meters(480.))
+                .map(|e| e.limiting.source)
+        );
+        store.expire(Length::meters(495.), Velocity::mps(0.));
+        assert_eq!(
+            Some(TargetSource::StopTarget(1)),
+            store
+                .envelope(Length::meters(495.))
+                .map(|e| e.limiting.source)
+        );
+        assert!(!store.overrun(Length::meters(1000.)));
+        assert!(store.overrun(Length::meters(1001.)));
+        store.expire(Length::meters(1001.), Velocity::mps(0.));
+        assert!(store.overrun(Length::meters(1001.)));
+    }
+    #[test]
+    fn expire_zone() {
+        let mut store = store();
+        let signal = target(1000., 0., TargetSource::SignalCode);
+        store.insert(signal);
+        store.insert(target(800., 5., TargetSource::SlowOrder(0)));
+        store.expire(Length::meters(800.), Velocity::mps(5.));
+        assert_eq!(
+            Some(TargetSource::SlowOrder(0)),
+            store
+                .envelope(Length::meters(800.))
+                .map(|e| e.limiting.source)
+        );
+        store.expire(Length::meters(800.1), Velocity::mps(5.));
+        assert_eq!(
+            Some(signal),
+            store.envelope(Length::meters(800.1)).map(|e| e.limiting)
+        );
+        // Entering a section with a stop code is not an overrun of the boundary
+        store.expire(Length::meters(1001.), Velocity::mps(5.));
+        assert!(!store.overrun(Length::meters(1001.)));
+        assert_eq!(None, store.envelope(Length::meters(1001.)));
+    }
+    #[test]
+    fn upcoming() {
+        let mut store = store();
+        let slow = target(1000., 5., TargetSource::Restriction(0));
+        store.insert(slow);
+        store.insert(target(1100., 0., TargetSource::StationStop));
+        // The curve towards `slow` starts at 928 m, 8 s away at 13 m/s
+        let horizon = Time::seconds(8.);
+        let speed = Velocity::mps(13.);
+        assert_eq!(None, store.upcoming(Length::meters(823.), speed, horizon));
+        assert_eq!(
+            Some(slow),
+            store.upcoming(Length::meters(824.), speed, horizon)
+        );
+        assert_eq!(
+            Some(slow),
+            store.upcoming(Length::meters(950.), speed, horizon)
+        );
+        assert_eq!(
+            None,
+            store.upcoming(Length::meters(824.), Velocity::mps(5.), horizon)
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use crate::bve::{Pressure, Time};
+use std::ffi::c_int;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Phase {
+    Released,
+    Applying { since: Time<c_int> },
+    Applied,
+    Releasing { since: Time<c_int> },
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct BrakeCheck {
+    // BC pressure did not reach the applied level in time; latched until reset
+    pub failure: bool,
+    // BC pressure stayed up after the ATC released while the driver was not braking
+    pub not_released: bool,
+}
+
+// Confirms through brake cylinder pressure that an ATC brake command takes effect.
+pub struct BrakeMonitor {
+    applied_pressure: Pressure,
+    apply_time: Time<c_int>,
+    released_pressure: Pressure,
+    release_time: Time<c_int>,
+    phase: Phase,
+    check: BrakeCheck,
+    // The running timer starts again on the next frame
+    restart: bool,
+}
+
+impl BrakeMonitor {
+    pub fn new(
+        applied_pressure: Pressure,
+        apply_time: Time<c_int>,
+        released_pressure: Pressure,
+        release_time: Time<c_int>,
+    ) -> Self {
+        Self {
+            applied_pressure,
+            apply_time,
+            released_pressure,
+            release_time,
+            phase: Phase::Released,
+            check: BrakeCheck {
+                failure: false,
+                not_released: false,
+            },
+            restart: false,
+        }
+    }
+    pub fn reset(&mut self) {
+        self.phase = Phase::Released;
+        self.check = BrakeCheck {
+            failure: false,
+            not_released: false,
+        };
+        self.restart = false;
+    }
+    // For a clock that jumped: the pending apply or release is timed afresh from the next
+    // frame, while a failure found so far stays latched
+    pub fn restart_timers(&mut self) {
+        self.restart = true;
+        self.check.not_released = false;
+    }
+    // `commanded` is whether the ATC brake is in force
+    pub fn update(
+        &mut self,
+        commanded: bool,
+        driver_braking: bool,
+        bc_pressure: Pressure,
+        time: Time<c_int>,
+    ) -> BrakeCheck {
+        if std::mem::take(&mut self.restart) {
+            self.phase = match self.phase {
+                Phase::Applying { .. } => Phase::Applying { since: time },
+                Phase::Releasing { .. } => Phase::Releasing { since: time },
+                phase => phase,
+            };
+        }
+        let elapsed = |since: Time<c_int>| time - since;
+        self.phase = match (self.phase, commanded) {
+            (Phase::Released | Phase::Releasing { .. }, true) => Phase::Applying { since: time },
+            (Phase::Applying { .. }, true) if bc_pressure >= self.applied_pressure => {
+                Phase::Applied
+            }
+            (Phase::Applying { since }, true) if elapsed(since) >= self.apply_time => {
+                self.check.failure = true;
+                self.phase
+            }
+            (Phase::Applying { .. } | Phase::Applied, false) => Phase::Releasing { since: time },
+            (Phase::Releasing { .. }, false)
+                if driver_braking || bc_pressure <= self.released_pressure =>
+            {
+                Phase::Released
+            }
+            (phase, _) => phase,
+        };
+        self.check.not_released = match self.phase {
+            Phase::Releasing { since } => elapsed(since) >= self.release_time,
+            _ => false,
+        };
+        self.check
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor() -> BrakeMonitor {
+        BrakeMonitor::new(
+            Pressure::kpa(150),
+            Time::seconds(3),
+            Pressure::kpa(50),
+            Time::seconds(5),
+        )
+    }
+    fn update(monitor: &mut BrakeMonitor, commanded: bool, bc: i32, seconds: i32) -> BrakeCheck {
+        monitor.update(commanded, false, Pressure::kpa(bc), Time::seconds(seconds))
+    }
+
+    #[test]
+    fn applied() {
+        let mut monitor = monitor();
+        assert!(!update(&mut monitor, true, 0, 0).failure);
+        assert!(!update(&mut monitor, true, 160, 2).failure);
+        assert!(!update(&mut monitor, true, 160, 10).failure);
+    }
+    #[test]
+    fn failure() {
+        let mut monitor = monitor();
+        update(&mut monitor, true, 0, 0);
+        assert!(!update(&mut monitor, true, 100, 2).failure);
+        assert!(update(&mut monitor, true, 100, 3).failure);
+        // Latched even once the command is gone
+        assert!(update(&mut monitor, false, 0, 4).failure);
+        monitor.reset();
+        assert!(!update(&mut monitor, false, 0, 5).failure);
+    }
+    #[test]
+    fn not_released() {
+        let mut monitor = monitor();
+        update(&mut monitor, true, 0, 0);
+        update(&mut monitor, true, 200, 1);
+        assert!(!update(&mut monitor, false, 200, 2).not_released);
+        assert!(!update(&mut monitor, false, 180, 6).not_released);
+        assert!(update(&mut monitor, false, 180, 7).not_released);
+        assert!(!update(&mut monitor, false, 40, 8).not_released);
+        // The driver's own brake keeps BC up legitimately
+        update(&mut monitor, true, 200, 10);
+        update(&mut monitor, false, 200, 11);
+        let check = monitor.update(false, true, Pressure::kpa(200), Time::seconds(20));
+        assert!(!check.not_released);
+    }
+    #[test]
+    fn restart_timers() {
+        let mut monitor = monitor();
+        update(&mut monitor, true, 0, 0);
+        // Clock rewound while applying: the apply time counts from the new clock
+        monitor.restart_timers();
+        assert!(!update(&mut monitor, true, 100, -60).failure);
+        assert!(!update(&mut monitor, true, 100, -58).failure);
+        assert!(update(&mut monitor, true, 100, -57).failure);
+        // A time skip does not clear a failure
+        monitor.restart_timers();
+        assert!(update(&mut monitor, true, 100, 100).failure);
+        assert!(update(&mut monitor, false, 0, 101).failure);
+    }
+}
@@ -1,51 +1,87 @@
-mod bve;
+pub mod bve;
+mod koatc;
 
 use crate::bve::{
-    Beacon, ConstantSpeed, HandleInitialPosition, Handles, Horn, Key, NotchPosition,
+    AtsModule, Beacon, HandleInitialPosition, Handles, Horn, Key, NotchPosition, PluginInstance,
     ReverserPosition, VehicleSpec, VehicleState,
 };
+use crate::koatc::KoAtc;
 use std::ffi::c_int;
 
+// The module every exported entry point is dispatched to
+static PLUGIN: PluginInstance<KoAtc> = PluginInstance::new();
+
 #[no_mangle]
-pub extern "system" fn Load() {}
+pub extern "system" fn Load() {
+    PLUGIN.load();
+}
 #[no_mangle]
-pub extern "system" fn Dispose() {}
+pub extern "system" fn Dispose() {
+    PLUGIN.dispose();
+}
 
 #[no_mangle]
-pub extern "system" fn SetVehicleSpec(_spec: VehicleSpec) {}
+pub extern "system" fn SetVehicleSpec(spec: VehicleSpec) {
+    PLUGIN.set_vehicle_spec(spec);
+}
 // Called when the game is started
 #[no_mangle]
-pub extern "system" fn Initialize(_brake: HandleInitialPosition) {}
+pub extern "system" fn Initialize(brake: c_int) {
+    if let Ok(brake) = HandleInitialPosition::try_from(brake) {
+        PLUGIN.with("Initialize", |m| m.initialize(brake));
+    }
+}
 #[no_mangle]
 pub extern "system" fn Elapse(
-    _state: VehicleState,
-    _panel: &mut [c_int; 256],
-    _sound: &mut [c_int; 256],
+    state: VehicleState,
+    panel: &mut [c_int; 256],
+    sound: &mut [c_int; 256],
 ) -> Handles {
-    Handles {
-        power: NotchPosition::NEUTRAL,
-        brake: NotchPosition::NEUTRAL,
-        reverser: ReverserPosition(0),
-        constant_speed: ConstantSpeed::Disable,
-    }
+    PLUGIN.tick(&state, panel, sound)
 }
 #[no_mangle]
-pub extern "system" fn SetPower(_power: c_int) {}
+pub extern "system" fn SetPower(power: c_int) {
+    PLUGIN.with("SetPower", |m| m.power(NotchPosition(power)));
+}
 #[no_mangle]
-pub extern "system" fn SetBrake(_brake: NotchPosition) {}
+pub extern "system" fn SetBrake(brake: NotchPosition) {
+    PLUGIN.with("SetBrake", |m| m.brake(brake));
+}
 #[no_mangle]
-pub extern "system" fn SetReverser(_reverser: ReverserPosition) {}
+pub extern "system" fn SetReverser(reverser: ReverserPosition) {
+    PLUGIN.reverser(reverser);
+}
 #[no_mangle]
-pub extern "system" fn KeyDown(_key: Key) {}
+pub extern "system" fn KeyDown(key: c_int) {
+    if let Ok(key) = Key::try_from(key) {
+        PLUGIN.with("KeyDown", |m| m.key_down(key));
+    }
+}
 #[no_mangle]
-pub extern "system" fn KeyUp(key: Key) {}
+pub extern "system" fn KeyUp(key: c_int) {
+    if let Ok(key) = Key::try_from(key) {
+        PLUGIN.with("KeyUp", |m| m.key_up(key));
+    }
+}
 #[no_mangle]
-pub extern "system" fn HornBlow(_horn: Horn) {}
+pub extern "system" fn HornBlow(horn: c_int) {
+    if let Ok(horn) = Horn::try_from(horn) {
+        PLUGIN.with("HornBlow", |m| m.horn_brow(horn));
+    }
+}
 #[no_mangle]
-pub extern "system" fn DoorOpen() {}
+pub extern "system" fn DoorOpen() {
+    PLUGIN.with("DoorOpen", |m| m.open_door());
+}
 #[no_mangle]
-pub extern "system" fn DoorClose() {}
+pub extern "system" fn DoorClose() {
+    PLUGIN.with("DoorClose", |m| m.close_door());
+}
 #[no_mangle]
-pub extern "system" fn SetSignal(_signal: c_int) {}
+pub extern "system" fn SetSignal(signal: c_int) {
+    PLUGIN.with("SetSignal", |m| m.set_signal(signal));
+}
 #[no_mangle]
-pub extern "system" fn SetBeaconData(_beacon: Beacon) {}
+pub extern "system" fn SetBeaconData(beacon: Beacon) {
+    PLUGIN.with("SetBeaconData", |m| m.receive_beacon(&beacon));
+}